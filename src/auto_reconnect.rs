@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use windows::core::GUID;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// How long an attempt may go without a completion or failure notification before it counts as failed.
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct ReconnectTarget {
+    pub guid: GUID,
+    pub profile_name: String,
+    pub ssid: String,
+}
+
+pub enum ReconnectStep {
+    /// Time to try again. `attempt` starts at 1.
+    Attempt { target: ReconnectTarget, attempt: u32 },
+    GaveUp(ReconnectTarget),
+}
+
+/// Retries the network we lost with exponential backoff, up to a retry cap.
+/// Connecting is left to the caller.
+pub struct AutoReconnect {
+    enabled: bool,
+    max_attempts: u32,
+    target: Option<ReconnectTarget>,
+    attempt: u32,
+    /// `None` while an attempt is in flight.
+    next_attempt: Option<Instant>,
+    /// Set while an attempt is in flight.
+    attempt_deadline: Option<Instant>,
+}
+
+impl Default for AutoReconnect {
+    fn default() -> Self {
+        AutoReconnect {
+            enabled: false,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            target: None,
+            attempt: 0,
+            next_attempt: None,
+            attempt_deadline: None,
+        }
+    }
+}
+
+impl AutoReconnect {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.cancel();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_max_attempts(&mut self, max_attempts: u32) {
+        self.max_attempts = max_attempts.max(1);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Starts retrying `target` after the first backoff. Returns false if it's disabled.
+    pub fn start(&mut self, target: ReconnectTarget) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        self.target = Some(target);
+        self.attempt = 0;
+        self.next_attempt = Some(Instant::now() + INITIAL_BACKOFF);
+        self.attempt_deadline = None;
+        true
+    }
+
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.attempt = 0;
+        self.next_attempt = None;
+        self.attempt_deadline = None;
+    }
+
+    /// Call regularly. Hands back the next attempt once its backoff is over.
+    pub fn poll(&mut self) -> Option<ReconnectStep> {
+        // The service can drop a request without ever reporting back
+        if self.attempt_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.attempt_failed();
+        }
+
+        let next_attempt = self.next_attempt?;
+        if Instant::now() < next_attempt {
+            return None;
+        }
+
+        let target = self.target.clone()?;
+        if self.attempt >= self.max_attempts {
+            self.cancel();
+            return Some(ReconnectStep::GaveUp(target));
+        }
+
+        self.attempt += 1;
+        self.next_attempt = None;
+        self.attempt_deadline = Some(Instant::now() + ATTEMPT_TIMEOUT);
+        Some(ReconnectStep::Attempt { target, attempt: self.attempt })
+    }
+
+    /// The in-flight attempt failed. Schedules the next one with a doubled backoff.
+    pub fn attempt_failed(&mut self) {
+        if self.target.is_none() {
+            return;
+        }
+
+        let backoff = INITIAL_BACKOFF
+            .saturating_mul(1 << self.attempt.min(6))
+            .min(MAX_BACKOFF);
+        self.next_attempt = Some(Instant::now() + backoff);
+        self.attempt_deadline = None;
+    }
+
+    /// Stops retrying without waiting for the cap. Returns the target if one was active.
+    pub fn give_up(&mut self) -> Option<ReconnectTarget> {
+        let target = self.target.take();
+        self.cancel();
+        target
+    }
+}
@@ -1,59 +1,42 @@
-use crate::wlan_enums::{convert_connection_notification, convert_msm_notification, ConnectionNotifcation};
-use crate::globals::CONNECTION_NOTIFICATION_CHANNEL;
+use crate::event_bus::{EventBus, InterfaceNotification};
+use crate::wlan_enums::{ConnectionNotifcation, RawNotification};
 use std::ffi::c_void;
-use std::ptr;
-use std::sync::mpsc::{Receiver, Sender};
-use std::sync::MutexGuard;
-use std::sync::TryLockError::{Poisoned, WouldBlock};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Instant;
+use windows::core::GUID;
 use windows::Win32::NetworkManagement::WiFi::*;
 
-pub extern "system" fn wlan_acm_notification_callback(notification: *mut L2_NOTIFICATION_DATA, _context: *mut c_void) {
-    let guard = match CONNECTION_NOTIFICATION_CHANNEL.try_lock() {
-        Ok(guard) => guard,
-        Err(error) => {
-            match error {
-                Poisoned(poison_error) => poison_error.into_inner(),
-                WouldBlock => return
-            }
-        },
-    };
-    
-    unsafe {
+/// `context` is the `EventBus` owned by the `NetworkManager` that registered the callback.
+pub extern "system" fn wlan_acm_notification_callback(notification: *mut L2_NOTIFICATION_DATA, context: *mut c_void) {
+    if context.is_null() {
+        return;
+    }
+
+    // Unwinding across the OS callback boundary is undefined behaviour, so swallow any panic here
+    let _ = catch_unwind(AssertUnwindSafe(|| unsafe {
+        let event_bus = &*(context as *const EventBus);
+
         let notif = if !notification.is_null() {
-            &ptr::read(notification)
+            &*notification
         } else {
-            send_notification_data(&guard, ConnectionNotifcation::Error);
+            event_bus.publish(InterfaceNotification {
+                guid: GUID::zeroed(),
+                notification: ConnectionNotifcation::Error,
+                raw: RawNotification::default(),
+                published_at: Instant::now(),
+            });
             return;
         };
-        
-        match notif.NotificationSource {
-            WLAN_NOTIFICATION_SOURCE_ACM => {
-                let notif_code = WLAN_NOTIFICATION_ACM(notif.NotificationCode as i32);
-                let notif_enum = convert_connection_notification(notif_code);
-                send_notification_data(&guard, notif_enum);
-            }
 
-            WLAN_NOTIFICATION_SOURCE_MSM => {
-                if !notif.pData.is_null() {
-                    let data_ref = ptr::read(notif.pData as *const u32);
-                    let notif_code = notif.NotificationCode;
+        // Copy the payload now, the OS frees it once the callback returns
+        let raw = RawNotification::from_notification(notif);
+        let Some(notif_enum) = raw.decode() else { return };
 
-                    let notif_enum = convert_msm_notification(notif_code, data_ref);
-                    send_notification_data(&guard, notif_enum);
-                } else {
-                    send_notification_data(&guard, ConnectionNotifcation::Error);
-                }
-            }
-
-            _ => {}
-        }
-    }
+        event_bus.publish(InterfaceNotification {
+            guid: notif.InterfaceGuid,
+            notification: notif_enum,
+            raw,
+            published_at: Instant::now(),
+        });
+    }));
 }
-
-fn send_notification_data(
-    guard: &MutexGuard<'_, (Sender<ConnectionNotifcation>, Receiver<ConnectionNotifcation>)>, 
-    data: ConnectionNotifcation) 
-    {
-    let sender = guard.0.clone();
-    sender.send(data).unwrap();
-}
\ No newline at end of file
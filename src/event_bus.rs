@@ -0,0 +1,234 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Instant;
+
+use arc_swap::ArcSwap;
+use crossbeam_queue::ArrayQueue;
+use windows::core::GUID;
+
+use crate::wlan_enums::{ConnectionNotifcation, RawNotification};
+
+#[derive(Debug, Clone)]
+pub struct InterfaceNotification {
+    pub guid: GUID,
+    pub notification: ConnectionNotifcation,
+    pub raw: RawNotification,
+    /// When the OS callback published it.
+    pub published_at: Instant,
+}
+
+pub const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverflowPolicy {
+    DropOldest,
+    DropNewest,
+}
+
+struct SubscriberQueue {
+    events: ArrayQueue<InterfaceNotification>,
+    policy: OverflowPolicy,
+    dropped: AtomicU64,
+}
+
+impl SubscriberQueue {
+    /// Lock-free. An event is only lost when the queue is full, and the policy picks which one.
+    fn push(&self, event: InterfaceNotification) {
+        let overflowed = match self.policy {
+            OverflowPolicy::DropOldest => self.events.force_push(event).is_some(),
+            OverflowPolicy::DropNewest => self.events.push(event).is_err(),
+        };
+
+        if overflowed {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Fans notifications out to every live subscriber. Publishing reads a snapshot of the subscriber
+/// list and pushes onto lock-free queues, so the OS callback never waits on a consumer.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: ArcSwap<Vec<Weak<SubscriberQueue>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn subscribe(&self, capacity: usize, policy: OverflowPolicy) -> Subscription {
+        let queue = Arc::new(SubscriberQueue {
+            events: ArrayQueue::new(capacity.max(1)),
+            policy,
+            dropped: AtomicU64::new(0),
+        });
+
+        // Swaps in a new list, a publish in progress keeps using the one it loaded
+        self.subscribers.rcu(|subscribers| {
+            let mut subscribers: Vec<_> = subscribers.iter()
+                .filter(|subscriber| subscriber.strong_count() > 0)
+                .cloned()
+                .collect();
+            subscribers.push(Arc::downgrade(&queue));
+            subscribers
+        });
+
+        Subscription { queue }
+    }
+
+    pub fn publish(&self, event: InterfaceNotification) {
+        for subscriber in self.subscribers.load().iter() {
+            if let Some(queue) = subscriber.upgrade() {
+                queue.push(event.clone());
+            }
+        }
+    }
+}
+
+/// Receiving end of an `EventBus`. Dropping it unsubscribes.
+pub struct Subscription {
+    queue: Arc<SubscriberQueue>,
+}
+
+impl Subscription {
+    pub fn try_recv(&self) -> Option<InterfaceNotification> {
+        self.queue.events.pop()
+    }
+
+    /// Takes what was queued when the call started. Events published meanwhile wait for the next drain.
+    pub fn drain(&self) -> Vec<InterfaceNotification> {
+        let count = self.queue.events.len();
+        (0..count).map_while(|_| self.queue.events.pop()).collect()
+    }
+
+    /// Events the overflow policy threw away since the last call.
+    pub fn take_dropped_count(&self) -> u64 {
+        self.queue.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn event(index: u32) -> InterfaceNotification {
+        InterfaceNotification {
+            guid: GUID::zeroed(),
+            notification: ConnectionNotifcation::SignalQualityChange(index),
+            raw: RawNotification::default(),
+            published_at: Instant::now(),
+        }
+    }
+
+    fn index_of(event: &InterfaceNotification) -> u32 {
+        match event.notification {
+            ConnectionNotifcation::SignalQualityChange(index) => index,
+            _ => panic!("unexpected notification {:?}", event.notification),
+        }
+    }
+
+    #[test]
+    fn no_event_is_lost_while_the_consumer_is_draining() {
+        const COUNT: u32 = 20_000;
+
+        let bus = EventBus::new();
+        let subscription = bus.subscribe(COUNT as usize, OverflowPolicy::DropNewest);
+
+        let publisher = {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for index in 0..COUNT {
+                    bus.publish(event(index));
+                }
+            })
+        };
+
+        let mut received = Vec::new();
+        while received.len() < COUNT as usize {
+            received.extend(subscription.drain().iter().map(index_of));
+            thread::yield_now();
+        }
+        publisher.join().unwrap();
+
+        assert_eq!(received, (0..COUNT).collect::<Vec<_>>());
+        assert_eq!(subscription.take_dropped_count(), 0);
+    }
+
+    #[test]
+    fn subscribing_during_publishing_loses_nothing_for_existing_subscribers() {
+        const COUNT: u32 = 5_000;
+
+        let bus = EventBus::new();
+        let subscription = bus.subscribe(COUNT as usize, OverflowPolicy::DropNewest);
+
+        let publisher = {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for index in 0..COUNT {
+                    bus.publish(event(index));
+                }
+            })
+        };
+
+        for _ in 0..200 {
+            drop(bus.subscribe(4, OverflowPolicy::DropOldest));
+        }
+        publisher.join().unwrap();
+
+        assert_eq!(subscription.drain().len(), COUNT as usize);
+        assert_eq!(subscription.take_dropped_count(), 0);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_newest_events() {
+        let bus = EventBus::new();
+        let subscription = bus.subscribe(3, OverflowPolicy::DropOldest);
+        for index in 0..5 {
+            bus.publish(event(index));
+        }
+
+        let received: Vec<u32> = subscription.drain().iter().map(index_of).collect();
+        assert_eq!(received, [2, 3, 4]);
+        assert_eq!(subscription.take_dropped_count(), 2);
+        assert_eq!(subscription.take_dropped_count(), 0);
+    }
+
+    #[test]
+    fn drop_newest_keeps_the_oldest_events() {
+        let bus = EventBus::new();
+        let subscription = bus.subscribe(3, OverflowPolicy::DropNewest);
+        for index in 0..5 {
+            bus.publish(event(index));
+        }
+
+        let received: Vec<u32> = subscription.drain().iter().map(index_of).collect();
+        assert_eq!(received, [0, 1, 2]);
+        assert_eq!(subscription.take_dropped_count(), 2);
+    }
+
+    #[test]
+    fn every_subscriber_gets_every_event() {
+        let bus = EventBus::new();
+        let first = bus.subscribe(8, OverflowPolicy::DropOldest);
+        let second = bus.subscribe(8, OverflowPolicy::DropOldest);
+        bus.publish(event(7));
+
+        assert_eq!(first.try_recv().map(|event| index_of(&event)), Some(7));
+        assert_eq!(second.try_recv().map(|event| index_of(&event)), Some(7));
+        assert!(first.try_recv().is_none());
+    }
+
+    #[test]
+    fn dropped_subscriptions_are_skipped() {
+        let bus = EventBus::new();
+        drop(bus.subscribe(8, OverflowPolicy::DropOldest));
+        let live = bus.subscribe(8, OverflowPolicy::DropOldest);
+        bus.publish(event(1));
+
+        assert_eq!(bus.subscribers.load().len(), 1);
+        assert_eq!(live.drain().len(), 1);
+    }
+}
@@ -0,0 +1,64 @@
+use godot::classes::{Engine, INode, Node};
+use godot::classes::node::ProcessMode;
+use godot::prelude::*;
+
+use crate::wlan_godot::WlanAPI;
+
+/// Drains every pending WLAN notification on the `WlanAPI` singleton from `_process`.
+/// `WlanAPI` adds one to the scene tree root when the network manager is initialized.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct WlanEventPump {
+    /// Seconds between drains. Zero drains every frame.
+    #[var]
+    pub(crate) poll_interval: f64,
+    #[var]
+    #[init(val = true)]
+    enabled: bool,
+    time_since_poll: f64,
+    base: Base<Node>
+}
+
+#[godot_api]
+impl INode for WlanEventPump {
+    fn ready(&mut self) {
+        // Connection events should still arrive while the game is paused
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+    }
+
+    fn process(&mut self, delta: f64) {
+        if !self.enabled {
+            return;
+        }
+
+        self.time_since_poll += delta;
+        if self.time_since_poll < self.poll_interval {
+            return;
+        }
+
+        self.time_since_poll = 0.0;
+        self.pump();
+    }
+}
+
+#[godot_api]
+impl WlanEventPump {
+    /// Drains the queue immediately and returns the number of notifications handled.
+    #[func]
+    pub fn pump(&mut self) -> i64 {
+        let Some(singleton) = Engine::singleton().get_singleton("WlanAPI") else {
+            godot_error!("[WLAN] Event Pump Could Not Find WlanAPI Singleton");
+            self.enabled = false;
+            return 0;
+        };
+
+        match singleton.try_cast::<WlanAPI>() {
+            Ok(mut wlan_api) => wlan_api.bind_mut().process_pending_notifications(),
+            Err(_) => {
+                godot_error!("[WLAN] WlanAPI Singleton Has The Wrong Type");
+                self.enabled = false;
+                0
+            },
+        }
+    }
+}
@@ -1,23 +1,17 @@
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::{Arc, LazyLock, Mutex};
-
-use crate::wlan_enums::ConnectionNotifcation;
-
-pub static CONNECTION_NOTIFICATION_CHANNEL: LazyLock<Arc<Mutex<(Sender<ConnectionNotifcation>, Receiver<ConnectionNotifcation>)>>> = LazyLock::new(|| {
-    Arc::new(Mutex::new(channel()))
-});
-
 #[cfg(debug_assertions)]
 pub fn save_xml_to_disk(ssid: &str) {
     use godot::global::{godot_error, godot_print};
 
-    use crate::profile_management::generate_network_profile_xml;
+    use crate::key_validation::derive_psk_hex;
+    use crate::profile_management::{generate_network_profile_xml, ConnectionPolicy};
     use crate::wlan_enums::{EncryptionAlgorithm, NetworkSecurity};
     use std::fs::File;
     use std::io::Write;
     use std::path::Path;
 
-    let xml_data = generate_network_profile_xml(ssid, "LOOKATMEEE", &EncryptionAlgorithm::AES, &NetworkSecurity::WPA2PSK);
+    // Debug exports get the derived PSK so the passphrase never ends up on disk
+    let key = derive_psk_hex("LOOKATMEEE", ssid.as_bytes());
+    let xml_data = generate_network_profile_xml(ssid, &key, &EncryptionAlgorithm::AES, &NetworkSecurity::WPA2PSK, &ConnectionPolicy::default(), 0);
 
     let path = Path::new("debug_profiles").join(format!("{}_profile.xml", ssid));
     if let Some(parent) = path.parent() {
@@ -38,4 +32,5 @@ pub fn save_xml_to_disk(ssid: &str) {
             godot_error!("Failed to create profile file: {:?}", e);
         }
     }
-}
\ No newline at end of file
+}
+
@@ -0,0 +1,411 @@
+use godot::prelude::*;
+use sha1::Sha1;
+
+use crate::wlan_enums::{EncryptionAlgorithm, NetworkSecurity};
+
+const WPA_PASSPHRASE_MIN: usize = 8;
+const WPA_PASSPHRASE_MAX: usize = 63;
+const RAW_PSK_LENGTH: usize = 64;
+/// From IEEE 802.11i, the PSK is PBKDF2-HMAC-SHA1 over the passphrase and SSID with this many rounds.
+const PSK_ITERATIONS: u32 = 4096;
+
+/// What kind of key a network takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyRequirement {
+    None,
+    Wpa,
+    /// WEP of either key size.
+    Wep,
+    /// 40-bit WEP, 5 characters or 10 hex digits.
+    Wep40,
+    /// 104-bit WEP, 13 characters or 26 hex digits.
+    Wep104,
+    /// Secured, but the scan didn't say how. Held to the WPA rules, since that is almost always what it is.
+    Any,
+}
+
+impl KeyRequirement {
+    pub fn for_network(security: &NetworkSecurity, encryption: &EncryptionAlgorithm, secured: bool) -> Self {
+        match encryption {
+            EncryptionAlgorithm::WEP40 => return KeyRequirement::Wep40,
+            EncryptionAlgorithm::WEP104 => return KeyRequirement::Wep104,
+            EncryptionAlgorithm::WEP => return KeyRequirement::Wep,
+            _ => {},
+        }
+
+        match security {
+            // Secured with open authentication only happens with WEP
+            NetworkSecurity::Open | NetworkSecurity::Shared if secured => KeyRequirement::Wep,
+            NetworkSecurity::Open | NetworkSecurity::Shared | NetworkSecurity::OWE => KeyRequirement::None,
+            NetworkSecurity::WPA | NetworkSecurity::WPA2 | NetworkSecurity::WPAPSK | NetworkSecurity::WPA2PSK
+            | NetworkSecurity::WPA3SAE => KeyRequirement::Wpa,
+            NetworkSecurity::Unknown if secured => KeyRequirement::Any,
+            NetworkSecurity::Unknown => KeyRequirement::None,
+        }
+    }
+
+    pub fn from_string(requirement: &str) -> Option<Self> {
+        match requirement.to_ascii_lowercase().as_str() {
+            "none" | "open" => Some(KeyRequirement::None),
+            "wpa" | "wpa2" | "wpa3" => Some(KeyRequirement::Wpa),
+            "wep" => Some(KeyRequirement::Wep),
+            "wep40" | "wep64" => Some(KeyRequirement::Wep40),
+            "wep104" | "wep128" => Some(KeyRequirement::Wep104),
+            "any" => Some(KeyRequirement::Any),
+            _ => None,
+        }
+    }
+
+    pub fn convert_to_string(&self) -> String {
+        match self {
+            KeyRequirement::None => String::from("none"),
+            KeyRequirement::Wpa => String::from("wpa"),
+            KeyRequirement::Wep => String::from("wep"),
+            KeyRequirement::Wep40 => String::from("wep40"),
+            KeyRequirement::Wep104 => String::from("wep104"),
+            KeyRequirement::Any => String::from("any"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyFormat {
+    /// Open network, the key is ignored.
+    None,
+    Passphrase,
+    /// 64 hex digits, used as the PSK directly.
+    RawPsk,
+    WepAscii,
+    WepHex,
+}
+
+impl KeyFormat {
+    /// The profile XML keyType for this format.
+    pub fn get_key_type(&self) -> &'static str {
+        match self {
+            KeyFormat::None | KeyFormat::Passphrase => "passPhrase",
+            KeyFormat::RawPsk | KeyFormat::WepAscii | KeyFormat::WepHex => "networkKey",
+        }
+    }
+
+    /// Key size of a WEP key of this format and `length`.
+    pub fn get_wep_key_bits(&self, length: usize) -> Option<u32> {
+        match (self, length) {
+            (KeyFormat::WepAscii, 5) | (KeyFormat::WepHex, 10) => Some(40),
+            (KeyFormat::WepAscii, 13) | (KeyFormat::WepHex, 26) => Some(104),
+            _ => None,
+        }
+    }
+
+    pub fn convert_to_string(&self) -> String {
+        match self {
+            KeyFormat::None => String::from("none"),
+            KeyFormat::Passphrase => String::from("passphrase"),
+            KeyFormat::RawPsk => String::from("raw_psk"),
+            KeyFormat::WepAscii => String::from("wep_ascii"),
+            KeyFormat::WepHex => String::from("wep_hex"),
+        }
+    }
+}
+
+/// Positions are 1-based character positions, for showing to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyValidationError {
+    Missing,
+    TooShort { length: usize, minimum: usize },
+    TooLong { length: usize, maximum: usize },
+    InvalidCharacter { position: usize, character: char },
+    NotHex { position: usize, character: char },
+    /// `bits` is set when the network's key size is known.
+    WrongWepLength { length: usize, bits: Option<u32> },
+}
+
+impl KeyValidationError {
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            KeyValidationError::Missing => "missing",
+            KeyValidationError::TooShort { .. } => "too_short",
+            KeyValidationError::TooLong { .. } => "too_long",
+            KeyValidationError::InvalidCharacter { .. } => "invalid_character",
+            KeyValidationError::NotHex { .. } => "not_hex",
+            KeyValidationError::WrongWepLength { .. } => "wrong_wep_length",
+        }
+    }
+
+    pub fn get_message(&self) -> String {
+        match self {
+            KeyValidationError::Missing => String::from("This Network Requires A Password"),
+            KeyValidationError::TooShort { length, minimum } => {
+                format!("Password Is {} Characters. It Must Be At Least {}", length, minimum)
+            },
+            KeyValidationError::TooLong { length, maximum } => {
+                format!("Password Is {} Characters. It Must Be At Most {}, Or Exactly 64 Hex Digits", length, maximum)
+            },
+            KeyValidationError::InvalidCharacter { position, character } => {
+                format!("Character {} ({:?}) Is Not Allowed. Use Printable ASCII Only", position, character)
+            },
+            KeyValidationError::NotHex { position, character } => {
+                format!("Character {} ({:?}) Is Not A Hex Digit", position, character)
+            },
+            KeyValidationError::WrongWepLength { length, bits: Some(40) } => {
+                format!("This Network Uses 40-Bit WEP, Which Takes 5 Characters Or 10 Hex Digits. This One Is {}", length)
+            },
+            KeyValidationError::WrongWepLength { length, bits: Some(104) } => {
+                format!("This Network Uses 104-Bit WEP, Which Takes 13 Characters Or 26 Hex Digits. This One Is {}", length)
+            },
+            KeyValidationError::WrongWepLength { length, .. } => {
+                format!("WEP Keys Are 5 Or 13 Characters, Or 10 Or 26 Hex Digits. This One Is {}", length)
+            },
+        }
+    }
+}
+
+fn find_non_printable(key: &str) -> Option<(usize, char)> {
+    key.chars()
+        .enumerate()
+        .find(|(_, character)| !(' '..='~').contains(character))
+        .map(|(index, character)| (index + 1, character))
+}
+
+fn find_non_hex(key: &str) -> Option<(usize, char)> {
+    key.chars()
+        .enumerate()
+        .find(|(_, character)| !character.is_ascii_hexdigit())
+        .map(|(index, character)| (index + 1, character))
+}
+
+pub fn validate_key(key: &str, requirement: KeyRequirement) -> Result<KeyFormat, KeyValidationError> {
+    if requirement == KeyRequirement::None {
+        return Ok(KeyFormat::None);
+    }
+
+    if key.is_empty() {
+        return Err(KeyValidationError::Missing);
+    }
+
+    if let Some((position, character)) = find_non_printable(key) {
+        return Err(KeyValidationError::InvalidCharacter { position, character });
+    }
+
+    // Past this point the key is ASCII, so its length in bytes is its length in characters
+    let length = key.len();
+
+    match requirement {
+        KeyRequirement::Wpa | KeyRequirement::Any => {
+            if length == RAW_PSK_LENGTH {
+                return match find_non_hex(key) {
+                    Some((position, character)) => Err(KeyValidationError::NotHex { position, character }),
+                    None => Ok(KeyFormat::RawPsk),
+                };
+            }
+
+            if length < WPA_PASSPHRASE_MIN {
+                return Err(KeyValidationError::TooShort { length, minimum: WPA_PASSPHRASE_MIN });
+            }
+
+            if length > WPA_PASSPHRASE_MAX {
+                return Err(KeyValidationError::TooLong { length, maximum: WPA_PASSPHRASE_MAX });
+            }
+
+            Ok(KeyFormat::Passphrase)
+        },
+        KeyRequirement::Wep | KeyRequirement::Wep40 | KeyRequirement::Wep104 => {
+            let bits = match requirement {
+                KeyRequirement::Wep40 => Some(40),
+                KeyRequirement::Wep104 => Some(104),
+                _ => None,
+            };
+
+            let format = match length {
+                5 | 13 => KeyFormat::WepAscii,
+                10 | 26 => match find_non_hex(key) {
+                    Some((position, character)) => return Err(KeyValidationError::NotHex { position, character }),
+                    None => KeyFormat::WepHex,
+                },
+                _ => return Err(KeyValidationError::WrongWepLength { length, bits }),
+            };
+
+            if bits.is_some() && format.get_wep_key_bits(length) != bits {
+                return Err(KeyValidationError::WrongWepLength { length, bits });
+            }
+
+            Ok(format)
+        },
+        KeyRequirement::None => Ok(KeyFormat::None),
+    }
+}
+
+/// Derives the 256-bit WPA PSK from a passphrase, the same way the supplicant would.
+pub fn derive_psk(passphrase: &str, ssid: &[u8]) -> [u8; 32] {
+    let mut psk = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha1>(passphrase.as_bytes(), ssid, PSK_ITERATIONS, &mut psk);
+    psk
+}
+
+/// The derived PSK as 64 hex digits, ready for a networkKey profile.
+pub fn derive_psk_hex(passphrase: &str, ssid: &[u8]) -> String {
+    derive_psk(passphrase, ssid).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The key material to save in a profile. With `derive_psk`, WPA/WPA2-Personal passphrases are
+/// swapped for their derived PSK so the passphrase itself never reaches the profile store.
+/// Everything else is kept as it is: WPA3-SAE authenticates with the passphrase itself, and a
+/// network of unknown security might not take a PSK.
+pub fn get_stored_key(key: &str, format: KeyFormat, security: &NetworkSecurity, ssid: &[u8], derive_psk: bool) -> String {
+    let takes_psk = matches!(security, NetworkSecurity::WPAPSK | NetworkSecurity::WPA2PSK);
+
+    match format {
+        KeyFormat::Passphrase if derive_psk && takes_psk => derive_psk_hex(key, ssid),
+        _ => key.to_string(),
+    }
+}
+
+/// Has valid, requirement and key_format keys, plus code and message when the key is invalid.
+pub fn validation_to_dictionary(result: &Result<KeyFormat, KeyValidationError>, requirement: KeyRequirement) -> Dictionary {
+    let mut dictionary = Dictionary::new();
+    dictionary.set("requirement", requirement.convert_to_string().as_str());
+
+    match result {
+        Ok(format) => {
+            dictionary.set("valid", true);
+            dictionary.set("key_format", format.convert_to_string().as_str());
+            dictionary.set("code", "");
+            dictionary.set("message", "");
+        },
+        Err(error) => {
+            dictionary.set("valid", false);
+            dictionary.set("key_format", "");
+            dictionary.set("code", error.get_code());
+            dictionary.set("message", error.get_message().as_str());
+        },
+    }
+
+    dictionary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // IEEE 802.11i-2004, Annex H.4
+    #[test]
+    fn derive_psk_matches_ieee_test_vectors() {
+        assert_eq!(
+            derive_psk_hex("password", b"IEEE"),
+            "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e");
+        assert_eq!(
+            derive_psk_hex("ThisIsAPassword", b"ThisIsASSID"),
+            "0dc0d6eb90555ed6419756b9a15ec3e3209b63df707dd508d14581f8982721af");
+        assert_eq!(
+            derive_psk_hex(&"a".repeat(32), "Z".repeat(32).as_bytes()),
+            "becb93866bb8c3832cb777c2f559807c8c59afcb6eae734885001300a981cc62");
+    }
+
+    #[test]
+    fn wpa_passphrase_length_bounds() {
+        assert_eq!(validate_key(&"a".repeat(8), KeyRequirement::Wpa), Ok(KeyFormat::Passphrase));
+        assert_eq!(validate_key(&"a".repeat(63), KeyRequirement::Wpa), Ok(KeyFormat::Passphrase));
+        assert_eq!(
+            validate_key(&"a".repeat(7), KeyRequirement::Wpa),
+            Err(KeyValidationError::TooShort { length: 7, minimum: 8 }));
+        assert_eq!(
+            validate_key(&"a".repeat(65), KeyRequirement::Wpa),
+            Err(KeyValidationError::TooLong { length: 65, maximum: 63 }));
+    }
+
+    #[test]
+    fn wpa_key_of_64_characters_must_be_hex() {
+        let raw_psk = "0123456789abcdef".repeat(4);
+        assert_eq!(validate_key(&raw_psk, KeyRequirement::Wpa), Ok(KeyFormat::RawPsk));
+        assert_eq!(validate_key(&raw_psk, KeyRequirement::Any), Ok(KeyFormat::RawPsk));
+
+        let not_hex = format!("{}g", &raw_psk[..63]);
+        assert_eq!(
+            validate_key(&not_hex, KeyRequirement::Wpa),
+            Err(KeyValidationError::NotHex { position: 64, character: 'g' }));
+    }
+
+    #[test]
+    fn unknown_secured_networks_are_held_to_the_wpa_bounds() {
+        assert_eq!(
+            validate_key("abc", KeyRequirement::Any),
+            Err(KeyValidationError::TooShort { length: 3, minimum: 8 }));
+        assert_eq!(validate_key(&"a".repeat(8), KeyRequirement::Any), Ok(KeyFormat::Passphrase));
+        assert_eq!(
+            validate_key(&"a".repeat(64), KeyRequirement::Any),
+            Ok(KeyFormat::RawPsk));
+        assert_eq!(
+            validate_key(&"a".repeat(70), KeyRequirement::Any),
+            Err(KeyValidationError::TooLong { length: 70, maximum: 63 }));
+    }
+
+    #[test]
+    fn wpa3_needs_a_wpa_key_and_enhanced_open_needs_none() {
+        let aes = EncryptionAlgorithm::AES;
+        assert_eq!(KeyRequirement::for_network(&NetworkSecurity::WPA3SAE, &aes, true), KeyRequirement::Wpa);
+        assert_eq!(KeyRequirement::for_network(&NetworkSecurity::OWE, &aes, true), KeyRequirement::None);
+        assert_eq!(KeyRequirement::for_network(&NetworkSecurity::Unknown, &aes, true), KeyRequirement::Any);
+        assert_eq!(KeyRequirement::for_network(&NetworkSecurity::Open, &EncryptionAlgorithm::WEP104, true), KeyRequirement::Wep104);
+    }
+
+    #[test]
+    fn rejects_missing_and_non_printable_keys() {
+        assert_eq!(validate_key("", KeyRequirement::Wpa), Err(KeyValidationError::Missing));
+        assert_eq!(
+            validate_key("pass\tword", KeyRequirement::Wpa),
+            Err(KeyValidationError::InvalidCharacter { position: 5, character: '\t' }));
+        assert_eq!(
+            validate_key("pässword", KeyRequirement::Wpa),
+            Err(KeyValidationError::InvalidCharacter { position: 2, character: 'ä' }));
+        assert_eq!(validate_key("", KeyRequirement::None), Ok(KeyFormat::None));
+    }
+
+    #[test]
+    fn wep_keys_by_length() {
+        assert_eq!(validate_key("abcde", KeyRequirement::Wep), Ok(KeyFormat::WepAscii));
+        assert_eq!(validate_key("abcdefghijklm", KeyRequirement::Wep), Ok(KeyFormat::WepAscii));
+        assert_eq!(validate_key("0123456789", KeyRequirement::Wep), Ok(KeyFormat::WepHex));
+        assert_eq!(validate_key(&"ab".repeat(13), KeyRequirement::Wep), Ok(KeyFormat::WepHex));
+
+        assert_eq!(
+            validate_key("012345678z", KeyRequirement::Wep),
+            Err(KeyValidationError::NotHex { position: 10, character: 'z' }));
+        assert_eq!(
+            validate_key("abcdef", KeyRequirement::Wep),
+            Err(KeyValidationError::WrongWepLength { length: 6, bits: None }));
+    }
+
+    #[test]
+    fn wep_key_must_match_the_network_key_size() {
+        assert_eq!(validate_key("abcde", KeyRequirement::Wep40), Ok(KeyFormat::WepAscii));
+        assert_eq!(validate_key("0123456789", KeyRequirement::Wep40), Ok(KeyFormat::WepHex));
+        assert_eq!(validate_key("abcdefghijklm", KeyRequirement::Wep104), Ok(KeyFormat::WepAscii));
+
+        assert_eq!(
+            validate_key("abcdefghijklm", KeyRequirement::Wep40),
+            Err(KeyValidationError::WrongWepLength { length: 13, bits: Some(40) }));
+        assert_eq!(
+            validate_key("0123456789", KeyRequirement::Wep104),
+            Err(KeyValidationError::WrongWepLength { length: 10, bits: Some(104) }));
+    }
+
+    #[test]
+    fn stored_key_is_derived_only_for_wpa_personal() {
+        let derived = derive_psk_hex("correct horse", b"Home");
+        let passphrase = KeyFormat::Passphrase;
+        assert_eq!(get_stored_key("correct horse", passphrase, &NetworkSecurity::WPA2PSK, b"Home", true), derived);
+        assert_eq!(get_stored_key("correct horse", passphrase, &NetworkSecurity::WPAPSK, b"Home", true), derived);
+
+        assert_eq!(get_stored_key("correct horse", passphrase, &NetworkSecurity::WPA2PSK, b"Home", false), "correct horse");
+        assert_eq!(get_stored_key("correct horse", passphrase, &NetworkSecurity::WPA3SAE, b"Home", true), "correct horse");
+        assert_eq!(get_stored_key("correct horse", passphrase, &NetworkSecurity::Unknown, b"Home", true), "correct horse");
+    }
+
+    #[test]
+    fn stored_key_keeps_wep_keys_and_raw_psks() {
+        assert_eq!(get_stored_key("abcde", KeyFormat::WepAscii, &NetworkSecurity::Open, b"Home", true), "abcde");
+
+        let raw_psk = "0".repeat(64);
+        assert_eq!(get_stored_key(&raw_psk, KeyFormat::RawPsk, &NetworkSecurity::WPA2PSK, b"Home", true), raw_psk);
+    }
+}
@@ -0,0 +1,352 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use godot::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::security_advisor::PassphraseStrength;
+use crate::wlan_enums::NetworkSecurity;
+
+pub const STORE_VERSION: u32 = 1;
+pub const DEFAULT_STORE_PATH: &str = "user://wlan_data/known_networks.json";
+
+/// Where `add_network_to_known_networks` used to write, relative to the working directory.
+const LEGACY_STORE_PATH: &str = "wlan_data/known_networks.txt";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KnownNetwork {
+    /// Raw SSID bytes, SSIDs are not guaranteed to be UTF-8.
+    pub ssid: Vec<u8>,
+    pub security: NetworkSecurity,
+    pub profile_name: String,
+    pub auto_connect: bool,
+    /// Lower values are tried first.
+    pub priority: u32,
+    /// Unix timestamps in seconds.
+    pub first_connected: Option<u64>,
+    pub last_connected: Option<u64>,
+    pub connect_count: u32,
+    #[serde(default)]
+    pub failure_count: u32,
+    /// Times this network turned out to be behind a captive portal.
+    #[serde(default)]
+    pub captive_portal_count: u32,
+    /// Rated when we saved the profile. The passphrase itself is never kept.
+    #[serde(default)]
+    pub passphrase_strength: Option<PassphraseStrength>,
+}
+
+impl KnownNetwork {
+    pub fn new(ssid: &[u8], security: NetworkSecurity, priority: u32) -> Self {
+        Self {
+            ssid: ssid.to_vec(),
+            security,
+            profile_name: String::from_utf8_lossy(ssid).to_string(),
+            auto_connect: true,
+            priority,
+            first_connected: None,
+            last_connected: None,
+            connect_count: 0,
+            failure_count: 0,
+            captive_portal_count: 0,
+            passphrase_strength: None,
+        }
+    }
+
+    /// Share of connection attempts that succeeded, or `None` before the first attempt.
+    pub fn get_success_rate(&self) -> Option<f64> {
+        let attempts = self.connect_count + self.failure_count;
+        if attempts == 0 {
+            return None;
+        }
+
+        Some(self.connect_count as f64 / attempts as f64)
+    }
+
+    pub fn get_ssid(&self) -> String {
+        String::from_utf8_lossy(&self.ssid).to_string()
+    }
+
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+        dictionary.set("ssid", self.get_ssid().as_str());
+        dictionary.set("security", self.security.convert_to_string().as_str());
+        dictionary.set("profile_name", self.profile_name.as_str());
+        dictionary.set("auto_connect", self.auto_connect);
+        dictionary.set("priority", self.priority as i64);
+        dictionary.set("first_connected", self.first_connected.map_or(-1, |time| time as i64));
+        dictionary.set("last_connected", self.last_connected.map_or(-1, |time| time as i64));
+        dictionary.set("connect_count", self.connect_count as i64);
+        dictionary.set("failure_count", self.failure_count as i64);
+        dictionary.set("captive_portal_count", self.captive_portal_count as i64);
+        dictionary.set("passphrase_strength", self.passphrase_strength.map_or(String::new(), |strength| strength.convert_to_string()).as_str());
+
+        dictionary
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    networks: Vec<KnownNetwork>,
+}
+
+pub struct KnownNetworkStore {
+    path: PathBuf,
+    networks: Vec<KnownNetwork>,
+}
+
+fn unix_time_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs())
+}
+
+impl KnownNetworkStore {
+    pub fn empty(path: PathBuf) -> Self {
+        Self { path, networks: Vec::new() }
+    }
+
+    /// Loads the store at `path`. When it doesn't exist yet, the old known_networks.txt is migrated into it.
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        if path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            let store_file: StoreFile = serde_json::from_str(&contents)?;
+
+            if store_file.version > STORE_VERSION {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                    "Store Version {} Is Newer Than Supported Version {}", store_file.version, STORE_VERSION)));
+            }
+
+            return Ok(Self { path, networks: store_file.networks });
+        }
+
+        let mut store = Self::empty(path);
+        store.migrate_legacy_file(Path::new(LEGACY_STORE_PATH))?;
+
+        Ok(store)
+    }
+
+    fn migrate_legacy_file(&mut self, legacy_path: &Path) -> io::Result<()> {
+        if !legacy_path.exists() {
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(legacy_path)?;
+        self.add_legacy_entries(&contents);
+        self.save()?;
+
+        // Keep the old file around under a new name in case the migration needs checking
+        fs::rename(legacy_path, legacy_path.with_extension("txt.migrated"))?;
+        godot_print!("[SYSTEM] Migrated {} Known Network(s) From {}", self.networks.len(), legacy_path.display());
+
+        Ok(())
+    }
+
+    /// The old file was one SSID per line, with no security information.
+    fn add_legacy_entries(&mut self, contents: &str) {
+        for ssid in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            self.add(ssid.as_bytes(), NetworkSecurity::Unknown);
+        }
+    }
+
+    /// Writes to a temporary file and renames it over the store, so a crash never leaves it half written.
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let store_file = StoreFile {
+            version: STORE_VERSION,
+            networks: self.networks.clone(),
+        };
+        let contents = serde_json::to_string_pretty(&store_file)?;
+
+        let temp_path = self.path.with_extension("json.tmp");
+        let mut temp_file = File::create(&temp_path)?;
+        temp_file.write_all(contents.as_bytes())?;
+        temp_file.sync_all()?;
+        drop(temp_file);
+
+        fs::rename(&temp_path, &self.path)
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_networks(&self) -> &[KnownNetwork] {
+        &self.networks
+    }
+
+    /// Looks the network up by its decoded SSID, which is all notifications and scripts have.
+    pub fn get(&self, ssid: &str) -> Option<&KnownNetwork> {
+        self.networks.iter().find(|network| network.get_ssid() == ssid)
+    }
+
+    pub fn get_mut(&mut self, ssid: &str) -> Option<&mut KnownNetwork> {
+        self.networks.iter_mut().find(|network| network.get_ssid() == ssid)
+    }
+
+    pub fn get_by_profile_mut(&mut self, profile_name: &str) -> Option<&mut KnownNetwork> {
+        self.networks.iter_mut().find(|network| network.profile_name == profile_name)
+    }
+
+    pub fn contains(&self, ssid: &str) -> bool {
+        self.get(ssid).is_some()
+    }
+
+    /// Adds the network at the lowest priority. Returns false if it was already known.
+    pub fn add(&mut self, ssid: &[u8], security: NetworkSecurity) -> bool {
+        if self.networks.iter().any(|network| network.ssid == ssid) {
+            return false;
+        }
+
+        let priority = self.networks.iter().map(|network| network.priority + 1).max().unwrap_or(0);
+        self.networks.push(KnownNetwork::new(ssid, security, priority));
+        true
+    }
+
+    pub fn remove(&mut self, ssid: &str) -> bool {
+        let count = self.networks.len();
+        self.networks.retain(|network| network.get_ssid() != ssid);
+
+        self.networks.len() != count
+    }
+
+    /// Updates the connection times and count. Returns false if the network isn't known.
+    pub fn record_connection(&mut self, ssid: &str) -> bool {
+        let Some(network) = self.get_mut(ssid) else { return false };
+        let now = unix_time_now();
+
+        network.first_connected.get_or_insert(now);
+        network.last_connected = Some(now);
+        network.connect_count += 1;
+        true
+    }
+
+    pub fn record_failure(&mut self, ssid: &str) -> bool {
+        let Some(network) = self.get_mut(ssid) else { return false };
+
+        network.failure_count += 1;
+        true
+    }
+
+    pub fn record_captive_portal(&mut self, ssid: &str) -> bool {
+        let Some(network) = self.get_mut(ssid) else { return false };
+
+        network.captive_portal_count += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store_path(test_name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("xpwm_{}_{}", std::process::id(), test_name));
+        let _ = fs::remove_dir_all(&directory);
+
+        directory.join("known_networks.json")
+    }
+
+    #[test]
+    fn loads_a_store_written_before_the_statistics_fields() {
+        let path = temp_store_path("old_fields");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{
+            "version": 1,
+            "networks": [{
+                "ssid": [72, 111, 109, 101],
+                "security": "WPA2PSK",
+                "profile_name": "Home",
+                "auto_connect": false,
+                "priority": 0,
+                "first_connected": 1700000000,
+                "last_connected": 1700000500,
+                "connect_count": 3
+            }]
+        }"#).unwrap();
+
+        let store = KnownNetworkStore::load(path.clone()).unwrap();
+        let network = store.get("Home").unwrap();
+        assert!(matches!(network.security, NetworkSecurity::WPA2PSK));
+        assert_eq!(network.connect_count, 3);
+        assert_eq!(network.failure_count, 0);
+        assert_eq!(network.captive_portal_count, 0);
+        assert!(network.passphrase_strength.is_none());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_newer_store_version() {
+        let path = temp_store_path("newer_version");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!(r#"{{ "version": {}, "networks": [] }}"#, STORE_VERSION + 1)).unwrap();
+
+        let error = KnownNetworkStore::load(path.clone()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn legacy_entries_become_known_networks_in_file_order() {
+        let mut store = KnownNetworkStore::empty(temp_store_path("legacy_entries"));
+        store.add_legacy_entries("Home\n\n  Office  \nHome\n");
+
+        let ssids: Vec<String> = store.get_networks().iter().map(KnownNetwork::get_ssid).collect();
+        assert_eq!(ssids, ["Home", "Office"]);
+        assert_eq!(store.get("Office").unwrap().priority, 1);
+        assert!(matches!(store.get("Home").unwrap().security, NetworkSecurity::Unknown));
+    }
+
+    #[test]
+    fn non_utf8_ssids_keep_their_raw_bytes() {
+        let path = temp_store_path("raw_ssid");
+        let raw_ssid = [b'C', b'a', b'f', 0xE9];
+        let mut store = KnownNetworkStore::empty(path.clone());
+        assert!(store.add(&raw_ssid, NetworkSecurity::WPA2PSK));
+        assert!(!store.add(&raw_ssid, NetworkSecurity::WPA2PSK));
+        store.save().unwrap();
+
+        let loaded = KnownNetworkStore::load(path.clone()).unwrap();
+        let network = &loaded.get_networks()[0];
+        assert_eq!(network.ssid, raw_ssid);
+        assert!(loaded.contains(&network.get_ssid()));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = temp_store_path("round_trip");
+        let mut store = KnownNetworkStore::empty(path.clone());
+        store.add(b"Home", NetworkSecurity::WPA2PSK);
+        store.add("Caf\u{e9}".as_bytes(), NetworkSecurity::Open);
+        store.record_connection("Home");
+        store.record_failure("Home");
+        store.record_captive_portal("Caf\u{e9}");
+        store.get_mut("Home").unwrap().passphrase_strength = Some(PassphraseStrength::Fair);
+        store.save().unwrap();
+
+        let loaded = KnownNetworkStore::load(path.clone()).unwrap();
+        assert_eq!(loaded.get_networks().len(), 2);
+
+        let home = loaded.get("Home").unwrap();
+        assert_eq!(home.connect_count, 1);
+        assert_eq!(home.failure_count, 1);
+        assert_eq!(home.get_success_rate(), Some(0.5));
+        assert!(home.last_connected.is_some());
+        assert_eq!(home.passphrase_strength, Some(PassphraseStrength::Fair));
+
+        let cafe = loaded.get("Caf\u{e9}").unwrap();
+        assert_eq!(cafe.captive_portal_count, 1);
+        assert_eq!(cafe.priority, 1);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
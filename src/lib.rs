@@ -3,9 +3,18 @@ mod utils;
 mod windows_api;
 mod wlan_godot;
 mod wlan_enums;
+mod auto_reconnect;
 mod callbacks;
+mod event_bus;
+mod event_pump;
 mod globals;
+mod key_validation;
+mod known_networks;
+mod network_selection;
 mod profile_management;
+mod roaming;
+mod security_advisor;
+mod trace;
 
 use godot::{classes::Engine, prelude::*};
 use windows_api::Win32API;
@@ -37,9 +46,9 @@ unsafe impl ExtensionLibrary for EWindowsAPI {
             let wlan_api = "WlanAPI";
             let win32_api = "Win32API";
 
-            if let Some(engine_singleton) = engine.get_singleton(wlan_api) {
-                if engine.has_method("close_wlan_handle") {
-                    engine.call("close_wlan_handle", &[]);
+            if let Some(mut engine_singleton) = engine.get_singleton(wlan_api) {
+                if engine_singleton.has_method("close_wlan_handle") {
+                    engine_singleton.call("close_wlan_handle", &[]);
                 }
 
                 engine.unregister_singleton(wlan_api);
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+use godot::prelude::*;
+
+use crate::networking::scanning::Band;
+use crate::wlan_enums::NetworkSecurity;
+
+/// Weight given to each new scan when smoothing signal quality.
+const DEFAULT_SMOOTHING: f64 = 0.3;
+/// How much better, out of 100, another network has to score before we leave the current one.
+const DEFAULT_SWITCH_MARGIN: f64 = 15.0;
+
+/// Everything a policy gets to see about one in-range known network.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub ssid: String,
+    pub profile_name: String,
+    /// 0 is the most preferred known network.
+    pub priority_rank: usize,
+    pub known_count: usize,
+    /// Smoothed over recent scans, 0 to 100.
+    pub signal_quality: f64,
+    pub band: Band,
+    pub security: NetworkSecurity,
+    pub success_rate: Option<f64>,
+    pub captive_portal_count: u32,
+    pub auto_connect: bool,
+    pub connected: bool,
+}
+
+impl Candidate {
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+        dictionary.set("ssid", self.ssid.as_str());
+        dictionary.set("profile_name", self.profile_name.as_str());
+        dictionary.set("priority_rank", self.priority_rank as i64);
+        dictionary.set("known_count", self.known_count as i64);
+        dictionary.set("signal_quality", self.signal_quality);
+        dictionary.set("band", self.band.convert_to_string().as_str());
+        dictionary.set("security", self.security.convert_to_string().as_str());
+        dictionary.set("success_rate", self.success_rate.unwrap_or(-1.0));
+        dictionary.set("captive_portal_count", self.captive_portal_count as i64);
+        dictionary.set("auto_connect", self.auto_connect);
+        dictionary.set("connected", self.connected);
+
+        dictionary
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CandidateScore {
+    pub candidate: Candidate,
+    /// Named parts of the score, in the order the policy added them.
+    pub components: Vec<(String, f64)>,
+    pub total: f64,
+    /// Set when the candidate can't be chosen regardless of its score.
+    pub rejected: Option<String>,
+}
+
+impl CandidateScore {
+    pub fn to_dictionary(&self, chosen: bool) -> Dictionary {
+        let mut components = Dictionary::new();
+        for (name, value) in &self.components {
+            components.set(name.as_str(), *value);
+        }
+
+        let mut dictionary = self.candidate.to_dictionary();
+        dictionary.set("components", components);
+        dictionary.set("total", self.total);
+        dictionary.set("eligible", self.rejected.is_none());
+        dictionary.set("rejected_reason", self.rejected.as_deref().unwrap_or(""));
+        dictionary.set("chosen", chosen);
+
+        dictionary
+    }
+}
+
+pub trait SelectionPolicy {
+    fn get_name(&self) -> &str;
+    fn score(&self, candidate: &Candidate) -> CandidateScore;
+}
+
+/// Adds up weighted 0 to 1 ratings, so the total tops out at the sum of the weights.
+#[derive(Debug, Clone)]
+pub struct WeightedPolicy {
+    pub priority: f64,
+    pub signal: f64,
+    pub band: f64,
+    pub security: f64,
+    pub reliability: f64,
+    /// Subtracted in full once a network has been behind a captive portal three times.
+    pub captive_portal: f64,
+}
+
+impl Default for WeightedPolicy {
+    fn default() -> Self {
+        WeightedPolicy {
+            priority: 30.0,
+            signal: 30.0,
+            band: 10.0,
+            security: 15.0,
+            reliability: 15.0,
+            captive_portal: 10.0,
+        }
+    }
+}
+
+impl WeightedPolicy {
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+        dictionary.set("priority", self.priority);
+        dictionary.set("signal", self.signal);
+        dictionary.set("band", self.band);
+        dictionary.set("security", self.security);
+        dictionary.set("reliability", self.reliability);
+        dictionary.set("captive_portal", self.captive_portal);
+
+        dictionary
+    }
+
+    /// Keys missing from `weights` keep their current value.
+    pub fn apply_dictionary(&mut self, weights: &Dictionary) {
+        let read = |key: &str, current: f64| {
+            weights.get(key).and_then(|value| value.try_to::<f64>().ok()).unwrap_or(current)
+        };
+
+        self.priority = read("priority", self.priority);
+        self.signal = read("signal", self.signal);
+        self.band = read("band", self.band);
+        self.security = read("security", self.security);
+        self.reliability = read("reliability", self.reliability);
+        self.captive_portal = read("captive_portal", self.captive_portal);
+    }
+}
+
+fn rate_band(band: Band) -> f64 {
+    match band {
+        Band::SixGhz => 1.0,
+        Band::FiveGhz => 0.8,
+        Band::TwoPointFourGhz => 0.5,
+        Band::Unknown => 0.5,
+    }
+}
+
+fn rate_security(security: &NetworkSecurity) -> f64 {
+    match security {
+        NetworkSecurity::WPA3SAE => 1.0,
+        NetworkSecurity::WPA2 | NetworkSecurity::WPA2PSK => 0.9,
+        NetworkSecurity::WPA | NetworkSecurity::WPAPSK => 0.5,
+        // Encrypted, but anyone can join
+        NetworkSecurity::OWE => 0.3,
+        NetworkSecurity::Unknown => 0.25,
+        NetworkSecurity::Shared => 0.1,
+        NetworkSecurity::Open => 0.0,
+    }
+}
+
+impl SelectionPolicy for WeightedPolicy {
+    fn get_name(&self) -> &str {
+        "weighted"
+    }
+
+    fn score(&self, candidate: &Candidate) -> CandidateScore {
+        let priority_rating = if candidate.known_count > 1 {
+            1.0 - candidate.priority_rank as f64 / (candidate.known_count - 1) as f64
+        } else {
+            1.0
+        };
+
+        // Networks we've never tried sit in the middle instead of at either end
+        let reliability_rating = candidate.success_rate.unwrap_or(0.5);
+        let captive_portal_rating = (candidate.captive_portal_count.min(3) as f64) / 3.0;
+
+        let components = vec![
+            (String::from("priority"), priority_rating * self.priority),
+            (String::from("signal"), (candidate.signal_quality / 100.0).clamp(0.0, 1.0) * self.signal),
+            (String::from("band"), rate_band(candidate.band) * self.band),
+            (String::from("security"), rate_security(&candidate.security) * self.security),
+            (String::from("reliability"), reliability_rating * self.reliability),
+            (String::from("captive_portal"), -captive_portal_rating * self.captive_portal),
+        ];
+
+        let rejected = if candidate.auto_connect {
+            None
+        } else {
+            Some(String::from("Auto-Connect Disabled"))
+        };
+
+        CandidateScore {
+            candidate: candidate.clone(),
+            total: components.iter().map(|(_, value)| value).sum(),
+            components,
+            rejected,
+        }
+    }
+}
+
+/// Lets GDScript score candidates. The callable gets the candidate dictionary and returns a float,
+/// or a dictionary with "total" and optional "components" and "rejected_reason" keys.
+pub struct CallablePolicy {
+    callable: Callable,
+}
+
+impl CallablePolicy {
+    pub fn new(callable: Callable) -> Self {
+        CallablePolicy { callable }
+    }
+}
+
+impl SelectionPolicy for CallablePolicy {
+    fn get_name(&self) -> &str {
+        "callable"
+    }
+
+    fn score(&self, candidate: &Candidate) -> CandidateScore {
+        let result = self.callable.call(&[candidate.to_dictionary().to_variant()]);
+
+        let mut score = CandidateScore {
+            candidate: candidate.clone(),
+            components: Vec::new(),
+            total: 0.0,
+            rejected: None,
+        };
+
+        if let Ok(total) = result.try_to::<f64>() {
+            score.total = total;
+            score.components.push((String::from("custom"), total));
+            return score;
+        }
+
+        let Ok(dictionary) = result.try_to::<Dictionary>() else {
+            godot_error!("[WLAN] Selection Callable Must Return A Float Or Dictionary");
+            score.rejected = Some(String::from("Policy Returned An Invalid Score"));
+            return score;
+        };
+
+        score.total = dictionary.get("total").and_then(|value| value.try_to::<f64>().ok()).unwrap_or(0.0);
+
+        if let Some(components) = dictionary.get("components").and_then(|value| value.try_to::<Dictionary>().ok()) {
+            for (name, value) in components.iter_shared() {
+                score.components.push((name.to_string(), value.try_to::<f64>().unwrap_or(0.0)));
+            }
+        }
+
+        score.rejected = dictionary.get("rejected_reason")
+            .map(|value| value.to_string())
+            .filter(|reason| !reason.is_empty());
+
+        score
+    }
+}
+
+pub enum Selection {
+    /// Connect to this profile.
+    Connect { profile_name: String, ssid: String },
+    /// The current network is still the best choice, or no other one beats it by enough.
+    Stay,
+    NoCandidates,
+}
+
+pub struct NetworkSelector {
+    policy: Box<dyn SelectionPolicy>,
+    smoothed_signal: HashMap<String, f64>,
+    smoothing: f64,
+    switch_margin: f64,
+    last_scores: Vec<CandidateScore>,
+    last_choice: Option<String>,
+}
+
+impl Default for NetworkSelector {
+    fn default() -> Self {
+        NetworkSelector {
+            policy: Box::new(WeightedPolicy::default()),
+            smoothed_signal: HashMap::new(),
+            smoothing: DEFAULT_SMOOTHING,
+            switch_margin: DEFAULT_SWITCH_MARGIN,
+            last_scores: Vec::new(),
+            last_choice: None,
+        }
+    }
+}
+
+impl NetworkSelector {
+    pub fn set_policy(&mut self, policy: Box<dyn SelectionPolicy>) {
+        godot_print!("[WLAN] Network Selection Policy Set To {}", policy.get_name());
+        self.policy = policy;
+    }
+
+    pub fn get_policy_name(&self) -> &str {
+        self.policy.get_name()
+    }
+
+    pub fn set_switch_margin(&mut self, margin: f64) {
+        self.switch_margin = margin.max(0.0);
+    }
+
+    pub fn get_switch_margin(&self) -> f64 {
+        self.switch_margin
+    }
+
+    /// Folds a scan's signal readings into the running averages. SSIDs that dropped out are forgotten.
+    pub fn observe_signals(&mut self, readings: &HashMap<String, u32>) {
+        self.smoothed_signal.retain(|ssid, _| readings.contains_key(ssid));
+
+        for (ssid, quality) in readings {
+            let quality = *quality as f64;
+            self.smoothed_signal.entry(ssid.clone())
+                .and_modify(|smoothed| *smoothed += self.smoothing * (quality - *smoothed))
+                .or_insert(quality);
+        }
+    }
+
+    pub fn get_smoothed_signal(&self, ssid: &str) -> Option<f64> {
+        self.smoothed_signal.get(ssid).copied()
+    }
+
+    /// Scores every candidate and decides whether to move. Scores are kept for `get_last_scores`.
+    pub fn evaluate(&mut self, candidates: &[Candidate]) -> Selection {
+        let mut scores: Vec<CandidateScore> = candidates.iter().map(|candidate| self.policy.score(candidate)).collect();
+        scores.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+        let best = scores.iter().find(|score| score.rejected.is_none()).cloned();
+        let current = scores.iter().find(|score| score.candidate.connected).cloned();
+        self.last_scores = scores;
+
+        let Some(best) = best else {
+            self.last_choice = None;
+            return Selection::NoCandidates;
+        };
+
+        if let Some(current) = current {
+            if best.candidate.connected || best.total < current.total + self.switch_margin {
+                self.last_choice = Some(current.candidate.ssid);
+                return Selection::Stay;
+            }
+        }
+
+        self.last_choice = Some(best.candidate.ssid.clone());
+        Selection::Connect {
+            profile_name: best.candidate.profile_name,
+            ssid: best.candidate.ssid,
+        }
+    }
+
+    pub fn get_last_scores(&self) -> &[CandidateScore] {
+        &self.last_scores
+    }
+
+    pub fn get_last_choice(&self) -> Option<&str> {
+        self.last_choice.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(ssid: &str, priority_rank: usize, signal_quality: f64) -> Candidate {
+        Candidate {
+            ssid: ssid.to_string(),
+            profile_name: ssid.to_string(),
+            priority_rank,
+            known_count: 3,
+            signal_quality,
+            band: Band::FiveGhz,
+            security: NetworkSecurity::WPA2PSK,
+            success_rate: None,
+            captive_portal_count: 0,
+            auto_connect: true,
+            connected: false,
+        }
+    }
+
+    fn chosen_ssid(selection: &Selection) -> Option<&str> {
+        match selection {
+            Selection::Connect { ssid, .. } => Some(ssid.as_str()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn priority_breaks_a_tie_in_signal() {
+        let mut selector = NetworkSelector::default();
+        let selection = selector.evaluate(&[candidate("Second", 1, 70.0), candidate("First", 0, 70.0)]);
+
+        assert_eq!(chosen_ssid(&selection), Some("First"));
+        assert_eq!(selector.get_last_choice(), Some("First"));
+    }
+
+    #[test]
+    fn equal_totals_keep_the_candidate_order() {
+        let mut selector = NetworkSelector::default();
+        let selection = selector.evaluate(&[candidate("Listed First", 0, 70.0), candidate("Listed Second", 0, 70.0)]);
+
+        assert_eq!(chosen_ssid(&selection), Some("Listed First"));
+    }
+
+    #[test]
+    fn wpa3_wins_over_wpa2_at_equal_signal() {
+        let mut wpa3 = candidate("WPA3", 1, 70.0);
+        wpa3.security = NetworkSecurity::WPA3SAE;
+
+        let mut selector = NetworkSelector::default();
+        assert_eq!(chosen_ssid(&selector.evaluate(&[candidate("WPA2", 1, 70.0), wpa3])), Some("WPA3"));
+        assert!(rate_security(&NetworkSecurity::OWE) > rate_security(&NetworkSecurity::Open));
+    }
+
+    #[test]
+    fn rejected_candidates_are_scored_but_never_chosen() {
+        let mut best = candidate("Best", 0, 100.0);
+        best.auto_connect = false;
+
+        let mut selector = NetworkSelector::default();
+        let selection = selector.evaluate(&[best, candidate("Fallback", 2, 40.0)]);
+        assert_eq!(chosen_ssid(&selection), Some("Fallback"));
+
+        // explain_choice lists every candidate best first, including the rejected one
+        let scores = selector.get_last_scores();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].candidate.ssid, "Best");
+        assert_eq!(scores[0].rejected.as_deref(), Some("Auto-Connect Disabled"));
+        assert!(scores[0].total > scores[1].total);
+        assert_eq!(scores[0].components.len(), 6);
+    }
+
+    #[test]
+    fn no_eligible_candidates() {
+        let mut only = candidate("Only", 0, 90.0);
+        only.auto_connect = false;
+
+        let mut selector = NetworkSelector::default();
+        assert!(matches!(selector.evaluate(&[only]), Selection::NoCandidates));
+        assert_eq!(selector.get_last_choice(), None);
+        assert!(matches!(selector.evaluate(&[]), Selection::NoCandidates));
+    }
+
+    #[test]
+    fn stays_unless_another_network_wins_by_the_switch_margin() {
+        let mut current = candidate("Current", 0, 50.0);
+        current.connected = true;
+        // 40 points of signal is 12 points of score with the default weights
+        let candidates = [current, candidate("Stronger", 0, 90.0)];
+
+        let mut selector = NetworkSelector::default();
+        assert!(matches!(selector.evaluate(&candidates), Selection::Stay));
+        assert_eq!(selector.get_last_choice(), Some("Current"));
+
+        selector.set_switch_margin(10.0);
+        assert_eq!(chosen_ssid(&selector.evaluate(&candidates)), Some("Stronger"));
+        assert_eq!(selector.get_last_choice(), Some("Stronger"));
+    }
+
+    #[test]
+    fn stays_when_the_connected_network_is_best() {
+        let mut current = candidate("Current", 0, 90.0);
+        current.connected = true;
+
+        let mut selector = NetworkSelector::default();
+        selector.set_switch_margin(0.0);
+        assert!(matches!(selector.evaluate(&[candidate("Other", 1, 40.0), current]), Selection::Stay));
+    }
+
+    #[test]
+    fn signal_is_smoothed_and_dropped_networks_forgotten() {
+        let mut selector = NetworkSelector::default();
+        selector.observe_signals(&HashMap::from([(String::from("Home"), 50), (String::from("Office"), 80)]));
+        assert_eq!(selector.get_smoothed_signal("Home"), Some(50.0));
+
+        selector.observe_signals(&HashMap::from([(String::from("Home"), 100)]));
+        let smoothed = selector.get_smoothed_signal("Home").unwrap();
+        assert!((smoothed - 65.0).abs() < 1e-9);
+        assert_eq!(selector.get_smoothed_signal("Office"), None);
+    }
+}
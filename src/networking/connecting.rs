@@ -1,25 +1,87 @@
-use std::{ffi::c_void, ptr::{addr_of, null_mut}};
+use std::mem::size_of;
+use std::ptr::null_mut;
 
-use windows::{core::PCWSTR, Win32::NetworkManagement::WiFi::*};
+use windows::{core::{GUID, PCWSTR}, Win32::NetworkManagement::{Ndis::{NDIS_OBJECT_HEADER, NDIS_OBJECT_TYPE_DEFAULT}, WiFi::*}};
 use widestring::*;
 use godot::prelude::*;
 
-use crate::windows_api::wlan::{self, WlanError};
+use crate::windows_api::{convert_u16_slice_to_string, opcodes::{self, ChannelNumber, CurrentConnection, Rssi}, wlan::{self, WlanBuffer, WlanError}};
+use crate::wlan_enums::*;
 
 use super::NetworkManager;
 
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub profile_name: String,
+    pub ssid: String,
+    pub bssid: [u8; 6],
+    pub phy_type: String,
+    pub signal_quality: u32,
+    pub rssi: Option<i32>,
+    pub rx_rate_kbps: u32,
+    pub tx_rate_kbps: u32,
+    pub channel: Option<u32>,
+    pub frequency_mhz: Option<u32>,
+    pub security_enabled: bool,
+    pub auth_algorithm: String,
+    pub cipher_algorithm: String,
+    pub one_x_enabled: bool,
+    pub connection_mode: String,
+}
+
 impl NetworkManager {
-    pub fn check_for_active_connection(&self) -> Option<&WLAN_CONNECTION_ATTRIBUTES> {
-        let mut data_size = 0u32;
+    pub fn get_connection_info(&self, guid: &GUID) -> Option<ConnectionInfo> {
+        let conn_attribs = self.check_for_active_connection(guid)?;
+
+        let association = conn_attribs.wlanAssociationAttributes;
+        let security = conn_attribs.wlanSecurityAttributes;
+        let ssid_raw = &association.dot11Ssid.ucSSID[..association.dot11Ssid.uSSIDLength as usize];
+
+        let profile_name = convert_u16_slice_to_string(&conn_attribs.strProfileName);
+        let connection_mode = convert_connection_mode_to_string(conn_attribs.wlanConnectionMode);
+
+        let rssi = self.query_opcode::<Rssi>(guid);
+        let channel = self.query_opcode::<ChannelNumber>(guid);
+
+        Some(ConnectionInfo {
+            profile_name,
+            ssid: String::from_utf8_lossy(ssid_raw).to_string(),
+            bssid: association.dot11Bssid,
+            phy_type: convert_phy_type_to_string(association.dot11PhyType),
+            signal_quality: association.wlanSignalQuality,
+            rssi,
+            rx_rate_kbps: association.ulRxRate,
+            tx_rate_kbps: association.ulTxRate,
+            channel,
+            frequency_mhz: self.get_frequency_mhz(guid, &association.dot11Bssid),
+            security_enabled: security.bSecurityEnabled.as_bool(),
+            auth_algorithm: convert_auth_algorithm_to_string(security.dot11AuthAlgorithm),
+            cipher_algorithm: convert_cipher_algorithm_to_string(security.dot11CipherAlgorithm),
+            one_x_enabled: security.bOneXEnabled.as_bool(),
+            connection_mode,
+        })
+    }
 
-        let op_code = wlan_intf_opcode_current_connection;
-        let mut op_type = wlan_opcode_value_type_query_only;
+    /// Channel numbers repeat across bands, so the frequency comes from the access point's BSS entry.
+    fn get_frequency_mhz(&self, guid: &GUID, bssid: &[u8; 6]) -> Option<u32> {
+        let cached = self.get_interface_data(guid)
+            .and_then(|data| data.bss_entries.iter().find(|entry| entry.bssid == *bssid))
+            .map(|entry| entry.frequency_mhz);
+
+        // After a roam the access point may not be in the last scan yet
+        cached.or_else(|| {
+            self.get_bss_entries(guid)
+                .into_iter()
+                .find(|entry| entry.bssid == *bssid)
+                .map(|entry| entry.frequency_mhz)
+        })
+    }
 
-        let ifo = self.interface_info.as_ref().unwrap();
+    pub fn check_for_active_connection(&self, guid: &GUID) -> Option<WlanBuffer<WLAN_CONNECTION_ATTRIBUTES>> {
         let client_handle = self.client_handle;
 
-        let query_result = match wlan::query_interface(client_handle, &ifo.InterfaceGuid, op_code, &mut data_size, &mut op_type) {
-            Ok(attribs) => attribs,
+        let query_result = match opcodes::query::<CurrentConnection>(client_handle, guid) {
+            Ok(attribs) => Some(attribs),
             Err(error) => match error {
                 WlanError::Error(err) => {
                     godot_error!("{}", err);
@@ -35,13 +97,13 @@ impl NetworkManager {
         query_result
     }
 
-    pub fn get_connected_network(&self) -> Option<String>  {
+    pub fn get_connected_network(&self, guid: &GUID) -> Option<String>  {
         if !self.is_handle_open || self.client_handle.is_invalid() {
             godot_error!("[WLAN] Handle Is Either Not Open Or Invalid");
             return None;
         }
 
-        let conn_attribs = match self.check_for_active_connection() {
+        let conn_attribs = match self.check_for_active_connection(guid) {
             Some(attribs) => attribs,
             None => return None,
         };
@@ -50,41 +112,59 @@ impl NetworkManager {
         let ssid_raw = &ssid.ucSSID[..ssid.uSSIDLength as usize];
         let ssid_string = String::from_utf8_lossy(ssid_raw).to_string();
 
-        unsafe {
-            godot_print!("[WLAN] Freeing query memory");
-            WlanFreeMemory(addr_of!(*conn_attribs) as *const c_void);
-        }
         Some(ssid_string)
     }
 
-    pub fn connect_to_known_network(&self, ssid: &str) {
+    /// Returns false if the request itself was refused. Otherwise the outcome arrives as a notification.
+    pub fn connect_to_known_network(&self, guid: &GUID, ssid: &str) -> bool {
         godot_print!("[WLAN] Connecting To Known Network: {}", ssid);
+        self.connect_with_profile(guid, ssid, None)
+    }
 
+    /// Connects through the profile but only to the access point with `bssid`.
+    pub fn connect_to_bssid(&self, guid: &GUID, ssid: &str, bssid: [u8; 6]) -> bool {
+        godot_print!("[WLAN] Connecting To {} On {}", ssid, convert_bssid_to_string(&bssid));
+        self.connect_with_profile(guid, ssid, Some(bssid))
+    }
+
+    fn connect_with_profile(&self, guid: &GUID, ssid: &str, desired_bssid: Option<[u8; 6]>) -> bool {
         let profile_name = U16CString::from_str(ssid).unwrap();
+
+        let mut bssid_list = desired_bssid.map(|bssid| DOT11_BSSID_LIST {
+            Header: NDIS_OBJECT_HEADER {
+                Type: NDIS_OBJECT_TYPE_DEFAULT as u8,
+                Revision: DOT11_BSSID_LIST_REVISION_1 as u8,
+                Size: size_of::<DOT11_BSSID_LIST>() as u16,
+            },
+            uNumOfEntries: 1,
+            uTotalNumOfEntries: 1,
+            BSSIDs: bssid,
+        });
+
         let conn_params = WLAN_CONNECTION_PARAMETERS {
             wlanConnectionMode: wlan_connection_mode_profile,
             strProfile: PCWSTR::from_raw(profile_name.as_ptr()),
             dot11BssType: dot11_BSS_type_infrastructure,
             pDot11Ssid: null_mut(),
-            pDesiredBssidList: null_mut(),
+            pDesiredBssidList: bssid_list.as_mut().map_or(null_mut(), |list| list as *mut DOT11_BSSID_LIST),
             dwFlags: 0
         };
         
-        let ifo = self.interface_info.unwrap();
-        let connect_result = wlan::connect(self.client_handle, &ifo.InterfaceGuid, &conn_params);
+        let connect_result = wlan::connect(self.client_handle, guid, &conn_params);
 
         if let Err(error) = connect_result {
             godot_error!("[WLAN] Failed to Connect to Network: {:?}", error);
-            return;
+            return false;
         } else {
             godot_print!("[WLAN] Connected to Network: {}", ssid);
         }
+
+        true
     }
 
-    pub fn disconnect_from_network(&self) {
+    pub fn disconnect_from_network(&self, guid: &GUID) {
         godot_print!("[WLAN] Disconnecting From Network");
-        let ifo = self.interface_info.unwrap().InterfaceGuid;
 
-        wlan::disconnect(self.client_handle, &ifo);
+        wlan::disconnect(self.client_handle, guid);
     }
 }
\ No newline at end of file
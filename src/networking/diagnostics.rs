@@ -0,0 +1,180 @@
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use godot::prelude::*;
+use windows::core::GUID;
+
+use crate::windows_api::ip_helper::{self, AdapterAddresses};
+
+use super::NetworkManager;
+
+const PROBE_HOST: &str = "www.msftconnecttest.com";
+const PROBE_PORT: u16 = 80;
+const PROBE_TIMEOUT_MS: u32 = 2000;
+
+#[derive(Debug, Clone, Default)]
+pub struct ProbeResult {
+    pub success: bool,
+    pub latency_ms: Option<u32>,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LinkDiagnostics {
+    pub ipv4_addresses: Vec<IpAddr>,
+    pub ipv6_addresses: Vec<IpAddr>,
+    pub gateways: Vec<IpAddr>,
+    pub dns_servers: Vec<IpAddr>,
+    pub gateway_probe: ProbeResult,
+    pub dns_probe: ProbeResult,
+    pub tcp_probe: ProbeResult,
+}
+
+impl NetworkManager {
+    fn get_adapter_addresses_for(&self, guid: &GUID) -> Option<AdapterAddresses> {
+        let adapters = match ip_helper::get_adapter_addresses() {
+            Ok(adapters) => adapters,
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Get Adapter Addresses: {:?}", error);
+                return None;
+            },
+        };
+
+        // IP Helper names adapters by the same GUID WLAN uses, wrapped in braces
+        let adapter_name = format!("{{{:?}}}", guid);
+        adapters.into_iter().find(|adapter| adapter.adapter_name.eq_ignore_ascii_case(&adapter_name))
+    }
+
+    /// Reads the addresses now and runs the probes on a worker thread, since each can take up to
+    /// `PROBE_TIMEOUT_MS`. The report arrives on the returned channel.
+    pub fn start_link_diagnostics(&self, guid: &GUID) -> Option<Receiver<LinkDiagnostics>> {
+        let adapter = self.get_adapter_addresses_for(guid)?;
+
+        let (ipv4_addresses, ipv6_addresses): (Vec<IpAddr>, Vec<IpAddr>) = adapter.unicast_addresses
+            .into_iter()
+            .partition(|address| address.is_ipv4());
+
+        let diagnostics = LinkDiagnostics {
+            ipv4_addresses,
+            ipv6_addresses,
+            gateways: adapter.gateway_addresses,
+            dns_servers: adapter.dns_server_addresses,
+            ..Default::default()
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("wlan-diagnostics".to_string())
+            .spawn(move || {
+                // The receiver may be gone by now, nobody is waiting for the report then
+                let _ = sender.send(run_probes(diagnostics));
+            });
+
+        match spawned {
+            Ok(_) => Some(receiver),
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Start Link Diagnostics: {}", error);
+                None
+            },
+        }
+    }
+}
+
+fn run_probes(mut diagnostics: LinkDiagnostics) -> LinkDiagnostics {
+    diagnostics.gateway_probe = probe_gateway(&diagnostics.gateways);
+
+    let (dns_probe, resolved) = probe_dns(PROBE_HOST);
+    diagnostics.dns_probe = dns_probe;
+
+    diagnostics.tcp_probe = match resolved {
+        Some(address) => probe_tcp(SocketAddr::new(address, PROBE_PORT)),
+        None => ProbeResult {
+            target: format!("{}:{}", PROBE_HOST, PROBE_PORT),
+            ..Default::default()
+        },
+    };
+
+    godot_print!("[WLAN] Link Diagnostics Complete: {:?}", diagnostics);
+    diagnostics
+}
+
+fn probe_gateway(gateways: &[IpAddr]) -> ProbeResult {
+    let gateway = match gateways.iter().find_map(|address| match address {
+        IpAddr::V4(v4) => Some(*v4),
+        IpAddr::V6(_) => None,
+    }) {
+        Some(gateway) => gateway,
+        None => {
+            godot_warn!("[WLAN] No IPv4 Gateway To Probe");
+            return ProbeResult::default();
+        },
+    };
+
+    let latency_ms = match ip_helper::icmp_echo(gateway, PROBE_TIMEOUT_MS) {
+        Ok(latency) => latency,
+        Err(error) => {
+            godot_error!("[WLAN] Failed To Ping Gateway: {:?}", error);
+            None
+        },
+    };
+
+    ProbeResult {
+        success: latency_ms.is_some(),
+        latency_ms,
+        target: gateway.to_string(),
+    }
+}
+
+fn probe_dns(host: &str) -> (ProbeResult, Option<IpAddr>) {
+    let start = Instant::now();
+
+    // The system resolver has no timeout of its own, so it runs on its own thread and is left
+    // behind if it takes longer than the other probes are allowed to
+    let (sender, receiver) = mpsc::channel();
+    let lookup_host = host.to_string();
+    thread::spawn(move || {
+        let _ = sender.send((lookup_host.as_str(), 0).to_socket_addrs());
+    });
+
+    let resolved = match receiver.recv_timeout(Duration::from_millis(PROBE_TIMEOUT_MS as u64)) {
+        Ok(Ok(mut addresses)) => addresses.next().map(|address| address.ip()),
+        Ok(Err(error)) => {
+            godot_warn!("[WLAN] DNS Resolution Of {} Failed: {}", host, error);
+            None
+        },
+        Err(_) => {
+            godot_warn!("[WLAN] DNS Resolution Of {} Timed Out", host);
+            None
+        },
+    };
+    let elapsed = start.elapsed().as_millis() as u32;
+
+    let result = ProbeResult {
+        success: resolved.is_some(),
+        latency_ms: resolved.map(|_| elapsed),
+        target: host.to_string(),
+    };
+
+    (result, resolved)
+}
+
+fn probe_tcp(address: SocketAddr) -> ProbeResult {
+    let start = Instant::now();
+    let timeout = Duration::from_millis(PROBE_TIMEOUT_MS as u64);
+
+    let latency_ms = match TcpStream::connect_timeout(&address, timeout) {
+        Ok(_) => Some(start.elapsed().as_millis() as u32),
+        Err(error) => {
+            godot_warn!("[WLAN] TCP Probe To {} Failed: {}", address, error);
+            None
+        },
+    };
+
+    ProbeResult {
+        success: latency_ms.is_some(),
+        latency_ms,
+        target: address.to_string(),
+    }
+}
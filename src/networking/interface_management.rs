@@ -1,14 +1,20 @@
-use std::{mem::ManuallyDrop, ptr::addr_of, slice};
-
+use windows::core::GUID;
 use windows::Win32::NetworkManagement::WiFi::*;
 use godot::prelude::*;
-use crate::{windows_api::wlan::{self, WlanResult}, wlan_enums::*};
+use crate::{windows_api::{opcodes::InterfaceState, wlan::{self, WlanResult}}, wlan_enums::*};
 
 use super::NetworkManager;
 
 impl NetworkManager {
     fn retrieve_interface_from_vec(&mut self, interfaces: Vec<WLAN_INTERFACE_INFO>) {
-        for interface in interfaces {
+        for interface in &interfaces {
+            let state = convert_wlan_interface_state(interface.isState);
+            self.get_interface_data_mut(&interface.InterfaceGuid).connection_state = state;
+        }
+
+        self.interface_data.retain(|guid, _| interfaces.iter().any(|ifo| ifo.InterfaceGuid == *guid));
+
+        for interface in &interfaces {
             let state = convert_wlan_interface_state(interface.isState);
             let ifo = interface.clone();
 
@@ -20,6 +26,8 @@ impl NetworkManager {
                 }
             }
         }
+
+        self.interfaces = interfaces;
     }
 
     fn get_interfaces(&self) -> Option<Vec<WLAN_INTERFACE_INFO>> {
@@ -32,36 +40,80 @@ impl NetworkManager {
             },
         };
 
-        let interfaces_len = interface_list.dwNumberOfItems as usize;
-        let interfaces_ptr = addr_of!(interface_list.InterfaceInfo);
-        unsafe {
-            let interfaces = slice::from_raw_parts(
-                interfaces_ptr.cast::<WLAN_INTERFACE_INFO>(),
-                interfaces_len);
-
-            let interface_vec = interfaces.to_vec();
-
-            let list_box = ManuallyDrop::into_inner(interface_list);
-            let raw_ptr = Box::into_raw(list_box);
-            WlanFreeMemory(raw_ptr as _);
-
-            Some(interface_vec)
-        }
+        Some(interface_list.items().to_vec())
     }
 
     pub fn initialize_interface_info(&mut self) {
         if self.interface_info.is_none() {
             godot_warn!("[WLAN] No Interface Info. Retrieving.");
-            let interfaces = match self.get_interfaces() {
-                Some(ifo) => ifo,
-                None => return,
-            };
-
-
-            self.retrieve_interface_from_vec(interfaces);
+            self.refresh_interfaces();
             godot_print!("[WLAN] Got Interface Info. Continuing With Scan");
         } else {
             godot_print!("[WLAN] Interface Info Present. Proceeding.");
         }
     }
-}
\ No newline at end of file
+
+    pub fn refresh_interfaces(&mut self) {
+        let interfaces = match self.get_interfaces() {
+            Some(ifo) => ifo,
+            None => return,
+        };
+
+        godot_print!("[WLAN] Found {} Interface(s)", interfaces.len());
+        self.retrieve_interface_from_vec(interfaces);
+    }
+
+    pub fn handle_interface_arrival(&mut self, guid: &GUID) {
+        godot_print!("[WLAN] Interface Arrived: {:?}", guid);
+        self.refresh_interfaces();
+    }
+
+    /// Drops a removed interface and fails over to another usable one if it was active.
+    /// Returns the newly selected interface when a failover happened.
+    pub fn handle_interface_removal(&mut self, guid: &GUID) -> Option<GUID> {
+        godot_print!("[WLAN] Interface Removed: {:?}", guid);
+
+        self.interfaces.retain(|ifo| ifo.InterfaceGuid != *guid);
+        self.interface_data.remove(guid);
+
+        if self.get_active_interface_guid() != Some(*guid) {
+            return None;
+        }
+
+        self.interface_info = None;
+        self.refresh_interfaces();
+
+        match self.get_active_interface_guid() {
+            Some(new_guid) => {
+                godot_print!("[WLAN] Failed Over To Interface {:?}", new_guid);
+                Some(new_guid)
+            },
+            None => {
+                godot_warn!("[WLAN] No Interfaces Left After Removal");
+                None
+            },
+        }
+    }
+
+    pub fn set_active_interface(&mut self, guid: &GUID) -> bool {
+        match self.find_interface(guid) {
+            Some(ifo) => {
+                self.interface_info = Some(ifo.clone());
+                godot_print!("[WLAN] Active Interface Set To {:?}", guid);
+                true
+            },
+            None => {
+                godot_error!("[WLAN] No Interface With GUID {:?}", guid);
+                false
+            },
+        }
+    }
+
+    /// Asks the service for the interface's state instead of waiting for a notification, and caches it.
+    pub fn refresh_interface_state(&mut self, guid: &GUID) -> Option<WlanInterfaceState> {
+        let state = convert_wlan_interface_state(self.query_opcode::<InterfaceState>(guid)?);
+        self.get_interface_data_mut(guid).connection_state = state;
+
+        Some(state)
+    }
+}
@@ -3,106 +3,170 @@ pub mod scanning;
 pub mod profile_management;
 pub mod interface_management;
 pub mod adapter_checking;
+pub mod diagnostics;
+pub mod radio;
+pub mod statistics;
+pub mod session;
 
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, ffi::c_void, rc::Rc, sync::Arc};
+use windows::core::GUID;
 use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::{Foundation::HANDLE, NetworkManagement::WiFi::*};
 use godot::prelude::*;
 
 use crate::callbacks;
+use crate::event_bus::{EventBus, OverflowPolicy, Subscription};
+use crate::windows_api::opcodes::{self, InterfaceOpcode, WritableOpcode};
 use crate::windows_api::wlan;
 use crate::wlan_enums::*;
 use crate::utils::*;
 
 pub struct NetworkManager {
-    pub networks: HashMap<Rc<String>, Network>,
+    pub interfaces: Vec<WLAN_INTERFACE_INFO>,
+    pub interface_data: HashMap<GUID, InterfaceData>,
     pub interface_info: Option<WLAN_INTERFACE_INFO>,
     pub client_handle: HANDLE,
     pub is_handle_open: bool,
     pub client_version: u32,
     pub negotiated_client_version: u32,
+    pub event_bus: Arc<EventBus>,
+    pub session: session::SessionHealth,
 }
 
 impl NetworkManager {
     pub fn new(client_handle: HANDLE, client_version: u32) -> Self {
         Self {
-            networks: HashMap::new(),
+            interfaces: Vec::new(),
+            interface_data: HashMap::new(),
             interface_info: None,
             client_handle,
             is_handle_open: false,
             client_version,
             negotiated_client_version: 0,
+            event_bus: EventBus::new(),
+            session: session::SessionHealth::default(),
         }
     }
 
+    /// When the service can't be reached yet, the session starts out lost so `check_session`
+    /// keeps retrying instead of staying down until the next `init`.
     pub fn init(&mut self) {
-        self.open_handle();
-        self.initialize_interface_info();
+        match self.open_handle() {
+            Ok(_) => self.initialize_interface_info(),
+            Err(_) => self.mark_session_lost(),
+        }
     }
 
-    pub fn open_handle(&mut self) {
+    pub fn open_handle(&mut self) -> Result<(), WIN32_ERROR> {
         if self.is_handle_open {
             godot_print!("[WLAN] Open Handle Already From Client");
-            return;
+            return Ok(());
         }
 
-        unsafe {
-            let handle_status = WlanOpenHandle(
+        let handle_status = unsafe {
+            WlanOpenHandle(
                 self.client_version,
                 None,
                 &mut self.negotiated_client_version,
-                &mut self.client_handle);
-
-            match check_win32(handle_status) {
-                Ok(_) => {
-                    godot_print!("[WLAN] Open Handle Ok");
-                    self.register_wlan_notification();
-                },
-                Err(e) => godot_error!("[WLAN] Open Handle Failed To Open Handle: {:?}", e)
-            }
+                &mut self.client_handle)
+        };
+
+        if let Err(e) = check_win32(handle_status) {
+            godot_error!("[WLAN] Open Handle Failed To Open Handle: {:?}", e);
+            self.client_handle = HANDLE::default();
+            return Err(e);
         }
 
+        godot_print!("[WLAN] Open Handle Ok");
         self.is_handle_open = true;
+        self.register_wlan_notification();
         godot_print!("[WLAN] Client Handle Opened");
+
+        Ok(())
     }
 
-    pub fn close_handle(&self) -> Result<(), WIN32_ERROR> {
-        if self.is_handle_open {
-            unsafe {
-                let status = WlanCloseHandle(self.client_handle, None);
-                check_win32(status)?;
-            }
-        } else {
+    pub fn close_handle(&mut self) -> Result<(), WIN32_ERROR> {
+        if !self.is_handle_open {
             godot_warn!("[WLAN] Attempted to Close a Non-Open Handle");
+            return Ok(());
         }
 
-        Ok(())
+        // Forget the handle even if closing it fails, it can't be used again either way
+        self.is_handle_open = false;
+        let handle = std::mem::take(&mut self.client_handle);
+
+        let status = unsafe { WlanCloseHandle(handle, None) };
+        check_win32(status)
     }
 
     fn register_wlan_notification(&self) {
         let handle = self.client_handle;
         let sources = WLAN_NOTIFICATION_SOURCE_ACM | WLAN_NOTIFICATION_SOURCE_MSM;
 
-        wlan::register_notification(handle, sources, false, Some(callbacks::wlan_acm_notification_callback));
+        // The bus outlives the registration: closing the handle unregisters before the Arc is dropped
+        let context = Arc::as_ptr(&self.event_bus) as *const c_void;
+        wlan::register_notification(handle, sources, false, Some(callbacks::wlan_acm_notification_callback), context);
+    }
+
+    pub fn subscribe(&self, capacity: usize, policy: OverflowPolicy) -> Subscription {
+        self.event_bus.subscribe(capacity, policy)
+    }
+
+    pub fn query_opcode<O: InterfaceOpcode>(&self, guid: &GUID) -> Option<O::Value> {
+        match opcodes::query::<O>(self.client_handle, guid) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                error.check("[WLAN] Failed To Query Interface");
+                None
+            },
+        }
+    }
+
+    pub fn set_opcode<O: WritableOpcode>(&self, guid: &GUID, value: O::Input) -> bool {
+        match opcodes::set::<O>(self.client_handle, guid, value) {
+            Ok(_) => true,
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Set Interface: {:?}", error);
+                false
+            },
+        }
     }
 
-    pub fn get_network(&self, ssid: &str) -> &Network {
-        let network = self.networks.get(&ssid.to_string()).unwrap();
-        network
+    pub fn get_network(&self, guid: &GUID, ssid: &str) -> Option<&Network> {
+        self.interface_data.get(guid)?.networks.get(&ssid.to_string())
     }
 }
 
 impl NetworkManager {
-    pub fn get_networks(&self) -> HashMap<Rc<String>, Network> {
-        self.networks.clone()
+    pub fn get_networks(&self, guid: &GUID) -> HashMap<Rc<String>, Network> {
+        match self.interface_data.get(guid) {
+            Some(data) => data.networks.clone(),
+            None => HashMap::new(),
+        }
+    }
+
+    pub fn get_interface_data(&self, guid: &GUID) -> Option<&InterfaceData> {
+        self.interface_data.get(guid)
+    }
+
+    pub fn get_interface_data_mut(&mut self, guid: &GUID) -> &mut InterfaceData {
+        self.interface_data.entry(*guid).or_default()
     }
 
     pub fn get_client_handle(&self) -> HANDLE {
         self.client_handle.clone()
     }
 
-    pub fn get_interface_info(&self) -> Option<&WLAN_INTERFACE_INFO> {
-        self.interface_info.as_ref()
+    pub fn get_active_interface_guid(&self) -> Option<GUID> {
+        self.interface_info.map(|ifo| ifo.InterfaceGuid)
+    }
+
+    pub fn get_interface_list(&self) -> &Vec<WLAN_INTERFACE_INFO> {
+        &self.interfaces
+    }
+
+    pub fn find_interface(&self, guid: &GUID) -> Option<&WLAN_INTERFACE_INFO> {
+        self.interfaces.iter().find(|ifo| ifo.InterfaceGuid == *guid)
     }
 }
 
@@ -117,33 +181,52 @@ impl Drop for NetworkManager {
     }
 }
 
+#[derive(Default)]
+pub struct InterfaceData {
+    pub networks: HashMap<Rc<String>, Network>,
+    pub connection_state: WlanInterfaceState,
+    pub radio_state: Option<radio::RadioState>,
+    pub last_statistics: Option<statistics::StatisticsSample>,
+    pub bss_entries: Vec<scanning::BssEntry>,
+    /// From the ConnectionStart or Associating notification, until the attempt completes or fails.
+    /// Also set for attempts Windows started on its own.
+    pub connecting_ssid: Option<String>,
+}
+
 #[derive(Clone)]
 #[allow(dead_code)]
 pub struct Network {
     pub ssid: Rc<String>,
+    /// The SSID as the scan reported it. `ssid` is decoded lossily for display.
+    pub ssid_bytes: Vec<u8>,
     pub secured: bool,
     pub connected: bool,
     pub network_security: NetworkSecurity,
     pub encryption: EncryptionAlgorithm,
-    pub bars: u32
+    pub bars: u32,
+    /// 0 to 100, as reported by the scan.
+    pub signal_quality: u32,
 }
 
 impl Network {
     pub fn new(
-        ssid: String, 
+        ssid_bytes: Vec<u8>, 
         secured: bool, 
         connected: bool, 
         network_security: NetworkSecurity,
         encryption: EncryptionAlgorithm,
-        bars: u32,) -> Self 
+        bars: u32,
+        signal_quality: u32,) -> Self 
     {
         Network {
-            ssid: Rc::new(ssid),
+            ssid: Rc::new(String::from_utf8_lossy(&ssid_bytes).to_string()),
+            ssid_bytes,
             secured,
             connected,
             network_security,
             encryption,
-            bars
+            bars,
+            signal_quality
         }
     }
 
@@ -151,6 +234,10 @@ impl Network {
         self.ssid.clone()
     }
 
+    pub fn get_ssid_bytes(&self) -> &[u8] {
+        &self.ssid_bytes
+    }
+
     pub fn get_security(&self) -> NetworkSecurity {
         self.network_security.clone()
     }
@@ -163,7 +250,16 @@ impl Network {
         self.bars
     }
 
+    pub fn get_signal_quality(&self) -> u32 {
+        self.signal_quality
+    }
+
     pub fn get_secured(&self) -> bool {
         self.secured
     }
+
+    /// Secured, but with WEP, which can be cracked in minutes.
+    pub fn is_insecure(&self) -> bool {
+        self.secured && (self.encryption.is_wep() || matches!(self.network_security, NetworkSecurity::Shared))
+    }
 }
\ No newline at end of file
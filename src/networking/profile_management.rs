@@ -1,17 +1,22 @@
-use std::{mem::ManuallyDrop, ptr::addr_of};
-use std::slice;
-
+use windows::core::GUID;
 use windows::Win32::NetworkManagement::WiFi::*;
 use godot::prelude::*;
 
-use crate::windows_api::{convert_string_to_u16cstring, convert_u16_slice_to_u16cstring, wlan};
+use crate::profile_management::ConnectionPolicy;
+use crate::windows_api::{convert_string_to_u16cstring, convert_u16_slice_to_string, convert_u16_slice_to_u16cstring, wlan};
 
 use super::NetworkManager;
 
+/// A profile in the interface's preferred networks list. Windows tries them in `position` order.
+pub struct PreferredProfile {
+    pub name: String,
+    pub position: u32,
+    pub policy: ConnectionPolicy,
+}
+
 impl NetworkManager {
-    pub fn check_for_windows_profiles(&self, profiles: &Vec<WLAN_PROFILE_INFO>) -> Option<(String, bool)> {
+    pub fn check_for_windows_profiles(&self, guid: &GUID, profiles: &Vec<WLAN_PROFILE_INFO>) -> Option<(String, bool)> {
         let client_handle = self.client_handle;
-        let guid = self.interface_info.unwrap().InterfaceGuid;
 
         for profile in profiles {
             let u16_cstring = match convert_u16_slice_to_u16cstring(&profile.strProfileName) {
@@ -19,10 +24,10 @@ impl NetworkManager {
                 None => return None,
             };
 
-            let retrieved_profile = match wlan::get_profile(client_handle, &guid, &u16_cstring) {
-                Ok(ret) => ret.to_string().unwrap(),
+            let retrieved_profile = match wlan::get_profile(client_handle, guid, &u16_cstring) {
+                Ok(ret) => ret.to_string_lossy(),
                 Err(error) => {
-                    godot_error!("[WLAN] Failed To Get Profile: {:?}", error);
+                    error.check("[WLAN] Failed To Get Profile");
                     return None;
                 },
             };
@@ -38,37 +43,92 @@ impl NetworkManager {
         None
     }
 
-    pub fn set_wlan_profile(&self, profile: &String) {
-        let ifo = self.interface_info.unwrap().InterfaceGuid;
+    pub fn set_wlan_profile(&self, guid: &GUID, profile: &String) -> bool {
         let profile_u16 = convert_string_to_u16cstring(profile).unwrap();
 
-        let set_profile_result = wlan::set_profile(self.client_handle, &ifo, &profile_u16, true);
+        let set_profile_result = wlan::set_profile(self.client_handle, guid, &profile_u16, true);
         if let Err(error) = set_profile_result {
             godot_error!("[DEBUG] Failed To Set Profile: {:?}", error);
-            return;
+            return false;
         };
+
+        true
     }
 
-    pub fn get_profile_list(&self, ifo: &WLAN_INTERFACE_INFO) -> Option<Vec<WLAN_PROFILE_INFO>> {
-        let profile_list = match wlan::get_profile_list(self.client_handle, &ifo.InterfaceGuid) {
+    pub fn get_profile_list(&self, guid: &GUID) -> Option<Vec<WLAN_PROFILE_INFO>> {
+        let profile_list = match wlan::get_profile_list(self.client_handle, guid) {
             Ok(result) => result,
             Err(error) => {
-                godot_error!("[WLAN] Failed To Get Profile List: {:?}", error);
+                error.check("[WLAN] Failed To Get Profile List");
                 return None;
             },
         };
 
-        let list_length = profile_list.dwNumberOfItems;
-        let info_ptr = addr_of!(profile_list.ProfileInfo);
-        let info_slice = unsafe {
-            slice::from_raw_parts(info_ptr.cast::<WLAN_PROFILE_INFO>(), list_length as usize)
-        }.to_vec();
+        Some(profile_list.items().to_vec())
+    }
+
+    pub fn get_profile_xml(&self, guid: &GUID, profile_name: &String) -> Option<String> {
+        let profile_u16 = convert_string_to_u16cstring(profile_name)?;
+
+        match wlan::get_profile(self.client_handle, guid, &profile_u16) {
+            Ok(profile) => Some(profile.to_string_lossy()),
+            Err(error) => {
+                error.check("[WLAN] Failed To Get Profile");
+                None
+            },
+        }
+    }
+
+    /// The profile list comes back from the service already in preference order.
+    pub fn get_preferred_profiles(&self, guid: &GUID) -> Option<Vec<PreferredProfile>> {
+        let profiles = self.get_profile_list(guid)?;
+        let mut preferred = Vec::with_capacity(profiles.len());
+
+        for (position, profile) in profiles.iter().enumerate() {
+            let name = convert_u16_slice_to_string(&profile.strProfileName);
+            let policy = match self.get_profile_xml(guid, &name) {
+                Some(profile_xml) => ConnectionPolicy::from_profile_xml(&profile_xml),
+                None => ConnectionPolicy::default(),
+            };
+
+            preferred.push(PreferredProfile { name, position: position as u32, policy });
+        }
 
-        unsafe {
-            let list_box = ManuallyDrop::into_inner(profile_list);
-            let raw_ptr = Box::into_raw(list_box);
-            WlanFreeMemory(raw_ptr as _);
+        Some(preferred)
+    }
+
+    pub fn set_profile_position(&self, guid: &GUID, profile_name: &String, position: u32) -> bool {
+        let Some(profile_u16) = convert_string_to_u16cstring(profile_name) else { return false };
+
+        match wlan::set_profile_position(self.client_handle, guid, &profile_u16, position) {
+            Ok(_) => true,
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Move Profile {} To Position {}: {:?}", profile_name, position, error);
+                false
+            },
+        }
+    }
+
+    /// Rewrites connectionMode and autoSwitch on a saved profile. `None` leaves that setting as it is.
+    pub fn set_profile_connection_policy(&self, guid: &GUID, profile_name: &String, auto_connect: Option<bool>, auto_switch: Option<bool>) -> bool {
+        let Some(profile_xml) = self.get_profile_xml(guid, profile_name) else { return false };
+
+        let mut policy = ConnectionPolicy::from_profile_xml(&profile_xml);
+        policy.auto_connect = auto_connect.unwrap_or(policy.auto_connect);
+        policy.auto_switch = auto_switch.unwrap_or(policy.auto_switch);
+
+        let Some(updated_xml) = policy.apply_to_profile_xml(&profile_xml) else {
+            godot_error!("[WLAN] Profile {} Has No connectionMode Element", profile_name);
+            return false;
+        };
+        let Some(profile_u16) = convert_string_to_u16cstring(&updated_xml) else { return false };
+
+        match wlan::set_profile(self.client_handle, guid, &profile_u16, true) {
+            Ok(_) => true,
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Update Profile {}: {:?}", profile_name, error);
+                false
+            },
         }
-        Some(info_slice)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,84 @@
+use windows::core::GUID;
+use windows::Win32::NetworkManagement::WiFi::*;
+use godot::prelude::*;
+
+use crate::windows_api::opcodes::{self, RadioState as RadioStateOpcode};
+use crate::windows_api::wlan::WlanError;
+
+use super::NetworkManager;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadioState {
+    pub phy_count: u32,
+    pub software_on: bool,
+    pub hardware_on: bool,
+}
+
+impl NetworkManager {
+    pub fn get_radio_state(&self, guid: &GUID) -> Option<RadioState> {
+        let phys = match opcodes::query::<RadioStateOpcode>(self.client_handle, guid) {
+            Ok(phys) => phys,
+            Err(error) => match error {
+                WlanError::Error(err) => {
+                    godot_error!("{}", err);
+                    return None;
+                },
+                WlanError::Win32Error(win32_error) => {
+                    godot_error!("[WLAN] Failed To Query Radio State: {:?}", win32_error);
+                    return None;
+                },
+            },
+        };
+
+        // Treat the adapter as on if any of its PHYs is on
+        Some(RadioState {
+            phy_count: phys.len() as u32,
+            software_on: phys.iter().any(|phy| phy.dot11SoftwareRadioState == dot11_radio_state_on),
+            hardware_on: phys.iter().any(|phy| phy.dot11HardwareRadioState == dot11_radio_state_on),
+        })
+    }
+
+    pub fn set_radio_state(&mut self, guid: &GUID, on: bool) -> bool {
+        let current = match self.get_radio_state(guid) {
+            Some(state) => state,
+            None => return false,
+        };
+
+        if !current.hardware_on {
+            godot_warn!("[WLAN] Hardware Radio Switch Is Off. Software State Can Still Be Changed.");
+        }
+
+        let software_state = if on { dot11_radio_state_on } else { dot11_radio_state_off };
+        for phy_index in 0..current.phy_count {
+            let phy_state = WLAN_PHY_RADIO_STATE {
+                dwPhyIndex: phy_index,
+                dot11SoftwareRadioState: software_state,
+                dot11HardwareRadioState: dot11_radio_state_unknown,
+            };
+
+            if let Err(error) = opcodes::set::<RadioStateOpcode>(self.client_handle, guid, phy_state) {
+                godot_error!("[WLAN] Failed To Set Radio State On PHY {}: {:?}", phy_index, error);
+                return false;
+            }
+        }
+
+        godot_print!("[WLAN] Radio Turned {}", if on { "On" } else { "Off" });
+
+        // Record the state we asked for so our own change isn't reported as an external one
+        self.get_interface_data_mut(guid).radio_state = Some(RadioState { software_on: on, ..current });
+        true
+    }
+
+    /// Re-reads the radio state and returns it if it differs from the last known state.
+    pub fn refresh_radio_state(&mut self, guid: &GUID) -> Option<RadioState> {
+        let new_state = self.get_radio_state(guid)?;
+        let interface_data = self.get_interface_data_mut(guid);
+
+        if interface_data.radio_state == Some(new_state) {
+            return None;
+        }
+
+        interface_data.radio_state = Some(new_state);
+        Some(new_state)
+    }
+}
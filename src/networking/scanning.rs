@@ -1,38 +1,182 @@
-use std::{ptr::{addr_of, null_mut, NonNull}, slice};
+use std::collections::HashMap;
 
-use windows::Win32::{Foundation::WIN32_ERROR, NetworkManagement::WiFi::*};
+use windows::core::GUID;
+use windows::Win32::NetworkManagement::WiFi::*;
 use godot::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::{utils::*, windows_api::wlan, wlan_enums::*};
+use crate::{utils::*, windows_api::wlan::{self, WlanError}, wlan_enums::*};
 
 use super::{Network, NetworkManager};
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BssEntry {
+    pub ssid: String,
+    pub bssid: [u8; 6],
+    pub rssi: i32,
+    /// 0 to 100.
+    pub link_quality: u32,
+    pub frequency_mhz: u32,
+    /// From the RSN information element, `None` when the access point doesn't send one.
+    pub rsn: Option<RsnInfo>,
+    /// The access point advertises original WPA through the Microsoft vendor element.
+    pub wpa: bool,
+}
+
+impl BssEntry {
+    pub fn get_band(&self) -> Band {
+        Band::from_frequency_mhz(self.frequency_mhz)
+    }
+}
+
+const ELEMENT_ID_RSN: u8 = 48;
+const ELEMENT_ID_VENDOR: u8 = 221;
+/// Microsoft OUI followed by OUI type 1, which marks the vendor element carrying WPA1.
+const WPA_VENDOR_PREFIX: [u8; 4] = [0x00, 0x50, 0xF2, 0x01];
+const IEEE_OUI: [u8; 3] = [0x00, 0x0F, 0xAC];
+
+/// Cipher suite types under the IEEE OUI.
+pub const CIPHER_SUITE_WEP40: u8 = 1;
+pub const CIPHER_SUITE_TKIP: u8 = 2;
+pub const CIPHER_SUITE_WEP104: u8 = 5;
+
+/// The parts of an RSN element the security advisor cares about. Suites from other OUIs are left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RsnInfo {
+    pub group_cipher: Option<u8>,
+    pub pairwise_ciphers: Vec<u8>,
+    pub akm_suites: Vec<u8>,
+    /// Management frame protection, 802.11w.
+    pub pmf_capable: bool,
+}
+
+impl RsnInfo {
+    /// Reads the element body, everything after the ID and length bytes. Elements cut short
+    /// keep whatever was read before the end, as the standard allows.
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        let mut reader = body;
+        let version = read_u16(&mut reader)?;
+        if version != 1 {
+            return None;
+        }
+
+        let mut rsn = RsnInfo::default();
+        let Some(suite) = read_suite(&mut reader) else { return Some(rsn) };
+        rsn.group_cipher = suite;
+
+        let Some(count) = read_u16(&mut reader) else { return Some(rsn) };
+        for _ in 0..count {
+            let Some(suite) = read_suite(&mut reader) else { return Some(rsn) };
+            rsn.pairwise_ciphers.extend(suite);
+        }
+
+        let Some(count) = read_u16(&mut reader) else { return Some(rsn) };
+        for _ in 0..count {
+            let Some(suite) = read_suite(&mut reader) else { return Some(rsn) };
+            rsn.akm_suites.extend(suite);
+        }
+
+        if let Some(capabilities) = read_u16(&mut reader) {
+            rsn.pmf_capable = capabilities & (1 << 7) != 0;
+        }
+
+        Some(rsn)
+    }
+
+    /// Every pairwise cipher on offer is TKIP.
+    pub fn is_tkip_only(&self) -> bool {
+        !self.pairwise_ciphers.is_empty() && self.pairwise_ciphers.iter().all(|cipher| *cipher == CIPHER_SUITE_TKIP)
+    }
+}
+
+fn read_u16(reader: &mut &[u8]) -> Option<u16> {
+    let (bytes, rest) = reader.split_first_chunk::<2>()?;
+    *reader = rest;
+    Some(u16::from_le_bytes(*bytes))
+}
+
+/// `Some(None)` for a suite from another vendor's OUI.
+fn read_suite(reader: &mut &[u8]) -> Option<Option<u8>> {
+    let (suite, rest) = reader.split_first_chunk::<4>()?;
+    *reader = rest;
+    Some((suite[..3] == IEEE_OUI).then_some(suite[3]))
+}
+
+/// Walks the information elements of a beacon or probe response for RSN and WPA1.
+fn parse_information_elements(mut elements: &[u8]) -> (Option<RsnInfo>, bool) {
+    let mut rsn = None;
+    let mut wpa = false;
+
+    while let [id, length, rest @ ..] = elements {
+        let length = *length as usize;
+        if rest.len() < length {
+            break;
+        }
+
+        let (body, remaining) = rest.split_at(length);
+        match *id {
+            ELEMENT_ID_RSN => rsn = RsnInfo::parse(body),
+            ELEMENT_ID_VENDOR if body.starts_with(&WPA_VENDOR_PREFIX) => wpa = true,
+            _ => {},
+        }
+
+        elements = remaining;
+    }
+
+    (rsn, wpa)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Band {
+    Unknown,
+    TwoPointFourGhz,
+    FiveGhz,
+    SixGhz,
+}
+
+impl Band {
+    pub fn from_frequency_mhz(frequency_mhz: u32) -> Self {
+        match frequency_mhz {
+            2400..=2500 => Band::TwoPointFourGhz,
+            4900..=5925 => Band::FiveGhz,
+            5926..=7125 => Band::SixGhz,
+            _ => Band::Unknown,
+        }
+    }
+
+    pub fn convert_to_string(&self) -> String {
+        match self {
+            Band::Unknown => String::from("Unknown"),
+            Band::TwoPointFourGhz => String::from("2.4GHz"),
+            Band::FiveGhz => String::from("5GHz"),
+            Band::SixGhz => String::from("6GHz"),
+        }
+    }
+}
+
 impl NetworkManager {
-    pub fn request_scan(&mut self) {
+    pub fn request_scan(&mut self, guid: &GUID) {
         godot_print!("[WLAN] Requesting Scan");
         let handle = self.client_handle;
-        let ifo = self.interface_info.unwrap().InterfaceGuid;
 
-        wlan::scan(handle, &ifo);
+        wlan::scan(handle, guid);
     }
 
-    pub fn refresh_networks(&mut self) {
+    pub fn refresh_networks(&mut self, guid: &GUID) {
         if !self.is_handle_open {
            godot_error!("[WLAN] A Client Handle Must Be Open to Scan for Networks!");
            return;
         }
 
-       self.networks.clear();
-       let ifo = self.interface_info.unwrap();
-
-       let new_network_list = match self.get_available_networks(&ifo) {
+       let new_network_list = match self.get_available_networks(guid) {
            Ok(new_network_list) => new_network_list,
            Err(e) => {
-               godot_error!("[WLAN] Failed to Get Available Networks: {:?}", e);
+               e.check("[WLAN] Failed to Get Available Networks");
                return;
            }
        };
 
+       let mut networks = HashMap::new();
        for network in new_network_list {
            let ssid_length = network.dot11Ssid.uSSIDLength as usize;
            if ssid_length > 32 {
@@ -40,62 +184,67 @@ impl NetworkManager {
            }
 
            let net = self.construct_network_object(&network, ssid_length);
-           self.networks.insert(net.get_ssid(), net);
+           networks.insert(net.get_ssid(), net);
        }
+
+       let bss_entries = self.get_bss_entries(guid);
+
+       let interface_data = self.get_interface_data_mut(guid);
+       interface_data.networks = networks;
+       interface_data.bss_entries = bss_entries;
    }
 
-    pub fn construct_network_object(&self, net: &WLAN_AVAILABLE_NETWORK, ssid_length: usize) -> Network {
-        let ssid_bytes = &net.dot11Ssid.ucSSID[..ssid_length];
-        let ssid = String::from_utf8_lossy(ssid_bytes);
+    /// Access points from the last scan. The available network list only has one entry per SSID.
+    pub fn get_bss_entries(&self, guid: &GUID) -> Vec<BssEntry> {
+        let bss_list = match wlan::get_network_bss_list(self.client_handle, guid) {
+            Ok(bss_list) => bss_list,
+            Err(e) => {
+                e.check("[WLAN] Failed to Get BSS List");
+                return Vec::new();
+            }
+        };
+
+        bss_list.items()
+            .iter()
+            .filter(|entry| entry.dot11Ssid.uSSIDLength as usize <= 32)
+            .map(|entry| {
+                let (rsn, wpa) = parse_information_elements(bss_list.information_elements(entry));
+
+                BssEntry {
+                    ssid: String::from_utf8_lossy(&entry.dot11Ssid.ucSSID[..entry.dot11Ssid.uSSIDLength as usize]).to_string(),
+                    bssid: entry.dot11Bssid,
+                    rssi: entry.lRssi,
+                    link_quality: entry.uLinkQuality,
+                    // Reported in kHz
+                    frequency_mhz: entry.ulChCenterFrequency / 1000,
+                    rsn,
+                    wpa,
+                }
+            })
+            .collect()
+    }
 
+    pub fn construct_network_object(&self, net: &WLAN_AVAILABLE_NETWORK, ssid_length: usize) -> Network {
         let (is_secured, security) = check_security(&net);
         let signal_strength = check_signal_strength(&net);
         let net_encryption = check_encryption(&net);
 
         let network = Network::new(
-            ssid.to_string(), 
+            net.dot11Ssid.ucSSID[..ssid_length].to_vec(),
             is_secured, false, 
             security,
             net_encryption,
-            signal_strength);
+            signal_strength,
+            net.wlanSignalQuality);
         network
     }
 
     fn get_available_networks(
         &self,
-        interface_info: &WLAN_INTERFACE_INFO
-    ) -> Result<Vec<WLAN_AVAILABLE_NETWORK> , WIN32_ERROR>
+        interface_guid: &GUID
+    ) -> Result<Vec<WLAN_AVAILABLE_NETWORK> , WlanError>
     {
-        unsafe {
-            let mut network_list_ptr: *mut WLAN_AVAILABLE_NETWORK_LIST = null_mut();
-            let result = WlanGetAvailableNetworkList(
-                self.client_handle,
-                &interface_info.InterfaceGuid,
-                0,
-                None,
-                &mut network_list_ptr
-            );
-            check_win32(result)?;
-
-            let network_list = match NonNull::new(network_list_ptr) {
-                Some(ptr) => ptr,
-                None => panic!("LIST PTR IS NULL")
-            };
-            let networks_ref = network_list.as_ref();
-
-            let networks_len = networks_ref.dwNumberOfItems;
-            let networks_ptr = addr_of!(networks_ref.Network);
-
-            let networks = slice::from_raw_parts(
-                networks_ptr.cast::<WLAN_AVAILABLE_NETWORK>(),
-                networks_len as usize
-            );
-
-            let networks_vec = networks.to_vec();
-            godot_print!("[WLAN] Freeing networks list memory");
-            WlanFreeMemory(network_list_ptr.cast());
-
-            Ok(networks_vec)
-        }
+        let network_list = wlan::get_available_network_list(self.client_handle, interface_guid)?;
+        Ok(network_list.items().to_vec())
     }
-}
\ No newline at end of file
+}
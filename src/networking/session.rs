@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use windows::core::GUID;
+use windows::Win32::Foundation::{ERROR_INVALID_HANDLE, ERROR_SERVICE_NOT_ACTIVE, WIN32_ERROR};
+use godot::prelude::*;
+
+use crate::windows_api::wlan::{self, WlanError, WlanResult};
+
+use super::NetworkManager;
+
+// RPC status codes returned while wlansvc is stopped or restarting
+const RPC_S_SERVER_UNAVAILABLE: WIN32_ERROR = WIN32_ERROR(1722);
+const RPC_S_CALL_FAILED: WIN32_ERROR = WIN32_ERROR(1726);
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct SessionHealth {
+    last_probe: Option<Instant>,
+    is_lost: bool,
+    /// Active interface when the service went away, re-selected once it is back.
+    lost_interface: Option<GUID>,
+    retry_attempt: u32,
+    next_retry: Option<Instant>,
+}
+
+pub enum SessionEvent {
+    Lost,
+    Restored { interface_changed: bool },
+}
+
+pub fn is_service_error(error: WIN32_ERROR) -> bool {
+    matches!(error, ERROR_INVALID_HANDLE | ERROR_SERVICE_NOT_ACTIVE | RPC_S_SERVER_UNAVAILABLE | RPC_S_CALL_FAILED)
+}
+
+impl NetworkManager {
+    pub fn is_service_lost(&self) -> bool {
+        self.session.is_lost
+    }
+
+    /// Probes the client handle every few seconds and, once the service is lost,
+    /// tries to reopen it with exponential backoff.
+    pub fn check_session(&mut self) -> Option<SessionEvent> {
+        let now = Instant::now();
+
+        if self.session.is_lost {
+            if let Some(next_retry) = self.session.next_retry {
+                if now < next_retry {
+                    return None;
+                }
+            }
+
+            return self.try_restore_session();
+        }
+
+        if !self.is_handle_open {
+            return None;
+        }
+
+        if let Some(last_probe) = self.session.last_probe {
+            if now.duration_since(last_probe) < PROBE_INTERVAL {
+                return None;
+            }
+        }
+        self.session.last_probe = Some(now);
+
+        match wlan::enumerate_interfaces(self.client_handle) {
+            WlanResult::Value(_) => None,
+            WlanResult::Error(WlanError::Win32Error(error)) if is_service_error(error) => {
+                godot_error!("[WLAN] Lost Connection To The WLAN Service: {:?}", error);
+                self.mark_session_lost();
+                Some(SessionEvent::Lost)
+            },
+            WlanResult::Error(error) => {
+                error.check("[WLAN] Session Health Check Failed");
+                None
+            },
+        }
+    }
+
+    pub fn mark_session_lost(&mut self) {
+        self.session.is_lost = true;
+        self.session.lost_interface = self.get_active_interface_guid();
+        self.session.retry_attempt = 0;
+        self.session.next_retry = Some(Instant::now() + INITIAL_BACKOFF);
+
+        // The service already dropped the handle, this only releases our side of it
+        if self.is_handle_open {
+            if let Err(error) = self.close_handle() {
+                godot_warn!("[WLAN] Closing Stale Client Handle Failed: {:?}", error);
+            }
+        }
+
+        self.interface_info = None;
+    }
+
+    fn try_restore_session(&mut self) -> Option<SessionEvent> {
+        self.session.retry_attempt += 1;
+
+        if self.open_handle().is_err() {
+            let backoff = INITIAL_BACKOFF
+                .saturating_mul(1 << self.session.retry_attempt.min(5))
+                .min(MAX_BACKOFF);
+
+            godot_warn!("[WLAN] WLAN Service Still Unavailable. Retrying In {}s", backoff.as_secs());
+            self.session.next_retry = Some(Instant::now() + backoff);
+            return None;
+        }
+
+        self.refresh_interfaces();
+
+        let lost_interface = self.session.lost_interface;
+        if let Some(guid) = lost_interface {
+            if self.find_interface(&guid).is_some() {
+                self.set_active_interface(&guid);
+            }
+        }
+
+        self.session = SessionHealth::default();
+        godot_print!("[WLAN] WLAN Service Restored");
+
+        Some(SessionEvent::Restored {
+            interface_changed: self.get_active_interface_guid() != lost_interface,
+        })
+    }
+}
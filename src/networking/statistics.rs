@@ -0,0 +1,136 @@
+use std::time::Instant;
+
+use windows::core::GUID;
+use windows::Win32::NetworkManagement::WiFi::*;
+use godot::prelude::*;
+
+use crate::windows_api::{ip_helper, opcodes};
+
+use super::NetworkManager;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InterfaceStatistics {
+    pub frames_sent: u64,
+    pub frames_received: u64,
+    pub multicast_frames_sent: u64,
+    pub multicast_frames_received: u64,
+    pub retries: u64,
+    pub multiple_retries: u64,
+    pub failed_transmissions: u64,
+    pub ack_failures: u64,
+    pub fcs_errors: u64,
+    pub decryption_errors: u64,
+    pub four_way_handshake_failures: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatisticsRates {
+    pub interval_secs: f64,
+    pub frames_sent_per_sec: f64,
+    pub frames_received_per_sec: f64,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    /// Retried transmissions as a fraction of frames sent in the interval.
+    pub retry_rate: f64,
+    /// Failed transmissions as a fraction of frames sent in the interval.
+    pub failure_rate: f64,
+    pub decryption_errors_per_sec: f64,
+}
+
+pub struct StatisticsSample {
+    pub statistics: InterfaceStatistics,
+    pub taken_at: Instant,
+}
+
+impl StatisticsRates {
+    pub fn between(previous: &StatisticsSample, current: &StatisticsSample) -> Option<Self> {
+        let interval_secs = current.taken_at.duration_since(previous.taken_at).as_secs_f64();
+        if interval_secs <= 0.0 {
+            return None;
+        }
+
+        let (old, new) = (&previous.statistics, &current.statistics);
+        let delta = |old_value: u64, new_value: u64| new_value.saturating_sub(old_value) as f64;
+        let ratio = |part: f64, whole: f64| if whole > 0.0 { part / whole } else { 0.0 };
+
+        let frames_sent = delta(old.frames_sent, new.frames_sent);
+
+        Some(Self {
+            interval_secs,
+            frames_sent_per_sec: frames_sent / interval_secs,
+            frames_received_per_sec: delta(old.frames_received, new.frames_received) / interval_secs,
+            rx_bytes_per_sec: delta(old.rx_bytes, new.rx_bytes) / interval_secs,
+            tx_bytes_per_sec: delta(old.tx_bytes, new.tx_bytes) / interval_secs,
+            retry_rate: ratio(delta(old.retries, new.retries), frames_sent),
+            failure_rate: ratio(delta(old.failed_transmissions, new.failed_transmissions), frames_sent),
+            decryption_errors_per_sec: delta(old.decryption_errors, new.decryption_errors) / interval_secs,
+        })
+    }
+}
+
+impl NetworkManager {
+    pub fn get_interface_statistics(&self, guid: &GUID) -> Option<InterfaceStatistics> {
+        let wlan_stats = match opcodes::query::<opcodes::Statistics>(self.client_handle, guid) {
+            Ok(stats) => stats,
+            Err(error) => {
+                error.check("[WLAN] Failed To Query Interface Statistics");
+                return None;
+            },
+        };
+
+        let mut statistics = InterfaceStatistics {
+            four_way_handshake_failures: wlan_stats.ullFourWayHandshakeFailures,
+            ..Default::default()
+        };
+
+        for mac in [&wlan_stats.MacUcastCounters, &wlan_stats.MacMcastCounters] {
+            statistics.decryption_errors += mac.ullDecryptFailureCount
+                + mac.ullCCMPDecryptErrors
+                + mac.ullTKIPICVErrorCount
+                + mac.ullWEPUndecryptableCount;
+        }
+
+        for phy in wlan_stats.phy_counters() {
+            statistics.frames_sent += phy.ullTransmittedFrameCount;
+            statistics.frames_received += phy.ullReceivedFrameCount;
+            statistics.multicast_frames_sent += phy.ullMulticastTransmittedFrameCount;
+            statistics.multicast_frames_received += phy.ullMulticastReceivedFrameCount;
+            statistics.retries += phy.ullRetryCount;
+            statistics.multiple_retries += phy.ullMultipleRetryCount;
+            statistics.failed_transmissions += phy.ullFailedCount;
+            statistics.ack_failures += phy.ullACKFailureCount;
+            statistics.fcs_errors += phy.ullFCSErrorCount;
+        }
+
+        match ip_helper::get_interface_octets(guid) {
+            Ok((rx_bytes, tx_bytes)) => {
+                statistics.rx_bytes = rx_bytes;
+                statistics.tx_bytes = tx_bytes;
+            },
+            Err(error) => godot_warn!("[WLAN] Failed To Read Byte Counters: {:?}", error),
+        }
+
+        Some(statistics)
+    }
+
+    /// Takes a new statistics sample and returns it along with the rates since the previous one.
+    pub fn sample_statistics(&mut self, guid: &GUID) -> Option<(InterfaceStatistics, Option<StatisticsRates>)> {
+        let current = StatisticsSample {
+            statistics: self.get_interface_statistics(guid)?,
+            taken_at: Instant::now(),
+        };
+
+        let interface_data = self.get_interface_data_mut(guid);
+        let rates = match &interface_data.last_statistics {
+            Some(previous) => StatisticsRates::between(previous, &current),
+            None => None,
+        };
+
+        let statistics = current.statistics;
+        interface_data.last_statistics = Some(current);
+
+        Some((statistics, rates))
+    }
+}
@@ -1,12 +1,86 @@
 use xmlwriter::XmlWriter;
 
+use crate::key_validation::{validate_key, KeyRequirement};
 use crate::wlan_enums::{EncryptionAlgorithm, NetworkSecurity};
 
+/// How Windows treats a profile once it's saved, as in XP's "Connect automatically when this network is in range".
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionPolicy {
+    pub auto_connect: bool,
+    /// Keep looking for more preferred networks while connected to this one.
+    pub auto_switch: bool,
+}
+
+impl Default for ConnectionPolicy {
+    fn default() -> Self {
+        ConnectionPolicy {
+            auto_connect: true,
+            auto_switch: false,
+        }
+    }
+}
+
+impl ConnectionPolicy {
+    pub fn connection_mode(&self) -> &'static str {
+        if self.auto_connect { "auto" } else { "manual" }
+    }
+
+    pub fn from_profile_xml(profile_xml: &str) -> Self {
+        ConnectionPolicy {
+            auto_connect: read_profile_element(profile_xml, "connectionMode").as_deref() == Some("auto"),
+            auto_switch: read_profile_element(profile_xml, "autoSwitch").as_deref() == Some("true"),
+        }
+    }
+
+    /// Rewrites connectionMode and autoSwitch in an existing profile, leaving the rest untouched.
+    pub fn apply_to_profile_xml(&self, profile_xml: &str) -> Option<String> {
+        let profile_xml = replace_profile_element(profile_xml, "connectionMode", self.connection_mode())?;
+        let auto_switch = if self.auto_switch { "true" } else { "false" };
+
+        match replace_profile_element(&profile_xml, "autoSwitch", auto_switch) {
+            Some(updated) => Some(updated),
+            None => {
+                // autoSwitch is optional and has to follow connectionMode when it's added
+                let insert_at = profile_xml.find("</connectionMode>")? + "</connectionMode>".len();
+                let mut updated = profile_xml.clone();
+                updated.insert_str(insert_at, &format!("<autoSwitch>{}</autoSwitch>", auto_switch));
+                Some(updated)
+            },
+        }
+    }
+}
+
+pub fn read_profile_element(profile_xml: &str, element_name: &str) -> Option<String> {
+    let open_tag = format!("<{}>", element_name);
+    let close_tag = format!("</{}>", element_name);
+
+    let start = profile_xml.find(&open_tag)? + open_tag.len();
+    let end = start + profile_xml[start..].find(&close_tag)?;
+
+    Some(profile_xml[start..end].trim().to_string())
+}
+
+fn replace_profile_element(profile_xml: &str, element_name: &str, value: &str) -> Option<String> {
+    let open_tag = format!("<{}>", element_name);
+    let close_tag = format!("</{}>", element_name);
+
+    let start = profile_xml.find(&open_tag)? + open_tag.len();
+    let end = start + profile_xml[start..].find(&close_tag)?;
+
+    let mut updated = profile_xml.to_string();
+    updated.replace_range(start..end, value);
+    Some(updated)
+}
+
+/// WEP keys can sit in any of four slots on the access point. `wep_key_index` picks the slot, 0 to 3,
+/// and is ignored for other networks.
 pub fn generate_network_profile_xml(
     ssid: &str, 
     password: &str, 
     encryption: &EncryptionAlgorithm,
-    security: &NetworkSecurity) -> String
+    security: &NetworkSecurity,
+    policy: &ConnectionPolicy,
+    wep_key_index: u32) -> String
 {
     let xml_options = xmlwriter::Options {
         use_single_quote: true,
@@ -16,12 +90,14 @@ pub fn generate_network_profile_xml(
 
     let mut writer = XmlWriter::new(xml_options);
 
-    let encryption_string = encryption.convert_to_string();
-    let security_string = security.convert_to_string();
+    let encryption_string = encryption.get_profile_value();
+    let security_string = security.get_profile_value();
     let ssid_hex = ssid.as_bytes().iter().map(|b| format!("{:02X}", b)).collect::<String>();
 
+    // WEP with open authentication still has a key, Enhanced Open never does
     let is_open = match security {
-        NetworkSecurity::Open => true,
+        NetworkSecurity::Open => !encryption.is_wep(),
+        NetworkSecurity::OWE => true,
         _ => false
     };
 
@@ -37,17 +113,23 @@ pub fn generate_network_profile_xml(
         write_element_preserve_white_spaces(&mut writer, "nonBroadcast", "false");
     writer.end_element(); // <SSIDConfig>
     write_element_preserve_white_spaces(&mut writer, "connectionType", "ESS");
-    write_element_preserve_white_spaces(&mut writer, "connectionMode", "manual");
-    write_element_preserve_white_spaces(&mut writer, "autoSwitch", "false");
+    write_element_preserve_white_spaces(&mut writer, "connectionMode", policy.connection_mode());
+    write_element_preserve_white_spaces(&mut writer, "autoSwitch", if policy.auto_switch { "true" } else { "false" });
     writer.start_element("MSM");
         writer.start_element("security");
             writer.start_element("authEncryption");
-                write_element_preserve_white_spaces(&mut writer, "authentication", &security_string);
-                write_element_preserve_white_spaces(&mut writer, "encryption", &encryption_string);
+                write_element_preserve_white_spaces(&mut writer, "authentication", security_string);
+                write_element_preserve_white_spaces(&mut writer, "encryption", encryption_string);
                 write_element_preserve_white_spaces(&mut writer, "useOneX", "false");
             writer.end_element(); // </authEncryption>
             if !is_open {
-                write_shared_key_element(&mut writer, password);
+                // 64 hex digits is a raw PSK, which Windows only accepts as a networkKey
+                let key_type = validate_key(password, KeyRequirement::for_network(security, encryption, true))
+                    .map_or("passPhrase", |format| format.get_key_type());
+                write_shared_key_element(&mut writer, key_type, password);
+            }
+            if encryption.is_wep() {
+                write_element_preserve_white_spaces(&mut writer, "keyIndex", &wep_key_index.min(3).to_string());
             }
         writer.end_element(); // </security>
     writer.end_element(); // </MSM>
@@ -64,9 +146,9 @@ fn write_element_preserve_white_spaces(writer: &mut XmlWriter, element_name: &st
     writer.set_preserve_whitespaces(false);
 }
 
-fn write_shared_key_element(writer: &mut XmlWriter, password: &str) {
+fn write_shared_key_element(writer: &mut XmlWriter, key_type: &str, password: &str) {
     writer.start_element("sharedKey");
-            write_element_preserve_white_spaces(writer, "keyType", "passPhrase");
+            write_element_preserve_white_spaces(writer, "keyType", key_type);
             write_element_preserve_white_spaces(writer, "protected", "false");
             write_element_preserve_white_spaces(writer, "keyMaterial", password);
     writer.end_element(); // </sharedKey>
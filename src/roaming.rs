@@ -0,0 +1,200 @@
+use std::time::{Duration, Instant};
+
+use crate::networking::scanning::BssEntry;
+
+const DEFAULT_THRESHOLD: u32 = 35;
+const DEFAULT_SUSTAIN: Duration = Duration::from_secs(5);
+const DEFAULT_HYSTERESIS: u32 = 20;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Signal values are WLAN signal quality, 0 to 100.
+#[derive(Debug, Clone)]
+pub struct RoamingConfig {
+    /// Quality below which the current BSS counts as weak.
+    pub threshold: u32,
+    /// How long the signal has to stay weak before we look for something better.
+    pub sustain: Duration,
+    /// How much stronger a target has to be than the current BSS.
+    pub hysteresis: u32,
+    /// Minimum time between roams.
+    pub cooldown: Duration,
+}
+
+impl Default for RoamingConfig {
+    fn default() -> Self {
+        RoamingConfig {
+            threshold: DEFAULT_THRESHOLD,
+            sustain: DEFAULT_SUSTAIN,
+            hysteresis: DEFAULT_HYSTERESIS,
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoamTarget {
+    pub profile_name: String,
+    pub ssid: String,
+    pub bssid: [u8; 6],
+    pub link_quality: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoamAttempt {
+    pub old_bssid: [u8; 6],
+    pub target: RoamTarget,
+}
+
+/// Watches the connected BSS's signal and decides when to scan and where to roam.
+/// Scanning and connecting are left to the caller.
+#[derive(Default)]
+pub struct RoamingAssistant {
+    pub config: RoamingConfig,
+    enabled: bool,
+    last_quality: Option<u32>,
+    weak_since: Option<Instant>,
+    awaiting_scan: bool,
+    last_roam: Option<Instant>,
+    in_progress: Option<RoamAttempt>,
+}
+
+impl RoamingAssistant {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.reset();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Forgets the signal history, for when the connection changes under us.
+    pub fn reset(&mut self) {
+        self.last_quality = None;
+        self.weak_since = None;
+        self.awaiting_scan = false;
+        self.in_progress = None;
+    }
+
+    pub fn observe_signal(&mut self, quality: u32) {
+        self.last_quality = Some(quality);
+
+        if quality >= self.config.threshold {
+            self.weak_since = None;
+        } else if self.weak_since.is_none() {
+            self.weak_since = Some(Instant::now());
+        }
+    }
+
+    /// True once the signal has been weak for the sustain period and a scan should be requested.
+    pub fn should_scan(&mut self) -> bool {
+        if !self.enabled || self.awaiting_scan || self.in_progress.is_some() || self.is_cooling_down() {
+            return false;
+        }
+
+        let Some(weak_since) = self.weak_since else { return false };
+        if weak_since.elapsed() < self.config.sustain {
+            return false;
+        }
+
+        self.awaiting_scan = true;
+        true
+    }
+
+    pub fn is_awaiting_scan(&self) -> bool {
+        self.awaiting_scan
+    }
+
+    /// The requested scan failed. Waits out another sustain period before trying again.
+    pub fn scan_failed(&mut self) {
+        if self.awaiting_scan {
+            self.awaiting_scan = false;
+            self.weak_since = self.weak_since.map(|_| Instant::now());
+        }
+    }
+
+    /// Picks the strongest other BSS on the current SSID. When none is strong enough,
+    /// falls back to the strongest BSS of `alternative`, a preferred network in range.
+    pub fn choose_target(
+        &mut self,
+        profile_name: &str,
+        ssid: &str,
+        current_bssid: &[u8; 6],
+        bss_entries: &[BssEntry],
+        alternative: Option<(&str, &str)>) -> Option<RoamTarget>
+    {
+        self.awaiting_scan = false;
+
+        // The signal may have recovered while the scan ran
+        let current_quality = self.last_quality?;
+        if current_quality >= self.config.threshold {
+            return None;
+        }
+
+        let target = self.find_target(profile_name, ssid, current_bssid, current_quality, bss_entries, alternative);
+        if target.is_none() {
+            // Nothing better yet. Wait out another sustain period before scanning again
+            self.weak_since = Some(Instant::now());
+        }
+
+        target
+    }
+
+    fn find_target(
+        &self,
+        profile_name: &str,
+        ssid: &str,
+        current_bssid: &[u8; 6],
+        current_quality: u32,
+        bss_entries: &[BssEntry],
+        alternative: Option<(&str, &str)>) -> Option<RoamTarget>
+    {
+        let required = current_quality + self.config.hysteresis;
+        let strongest = |ssid: &str| {
+            bss_entries.iter()
+                .filter(|entry| entry.ssid == ssid && entry.bssid != *current_bssid && entry.link_quality >= required)
+                .max_by_key(|entry| entry.link_quality)
+        };
+
+        if let Some(entry) = strongest(ssid) {
+            return Some(RoamTarget {
+                profile_name: profile_name.to_string(),
+                ssid: ssid.to_string(),
+                bssid: entry.bssid,
+                link_quality: entry.link_quality,
+            });
+        }
+
+        let (alternative_profile, alternative_ssid) = alternative?;
+        strongest(alternative_ssid).map(|entry| RoamTarget {
+            profile_name: alternative_profile.to_string(),
+            ssid: alternative_ssid.to_string(),
+            bssid: entry.bssid,
+            link_quality: entry.link_quality,
+        })
+    }
+
+    pub fn start_roam(&mut self, old_bssid: [u8; 6], target: RoamTarget) {
+        self.in_progress = Some(RoamAttempt { old_bssid, target });
+        self.weak_since = None;
+    }
+
+    pub fn is_roaming(&self) -> bool {
+        self.in_progress.is_some()
+    }
+
+    /// Ends the current roam, successful or not, and starts the cooldown.
+    pub fn finish_roam(&mut self) -> Option<RoamAttempt> {
+        let attempt = self.in_progress.take()?;
+        self.last_roam = Some(Instant::now());
+        self.last_quality = None;
+
+        Some(attempt)
+    }
+
+    fn is_cooling_down(&self) -> bool {
+        self.last_roam.is_some_and(|last_roam| last_roam.elapsed() < self.config.cooldown)
+    }
+}
@@ -0,0 +1,282 @@
+use godot::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::networking::Network;
+use crate::networking::scanning::{BssEntry, CIPHER_SUITE_TKIP, CIPHER_SUITE_WEP104, CIPHER_SUITE_WEP40};
+use crate::profile_management::read_profile_element;
+use crate::wlan_enums::{EncryptionAlgorithm, NetworkSecurity};
+
+/// Names routers ship with, compared case-insensitively.
+const DEFAULT_SSIDS: [&str; 10] = [
+    "default", "linksys", "netgear", "dlink", "d-link", "belkin54g", "wireless", "tsunami", "2wire", "home",
+];
+/// Prefixes of names routers generate from their model or MAC address.
+const DEFAULT_SSID_PREFIXES: [&str; 8] = [
+    "tp-link_", "dlink-", "belkin.", "netgear", "linksys", "asus_", "2wire", "wlan-",
+];
+const COMMON_PASSPHRASES: [&str; 12] = [
+    "password", "password1", "12345678", "123456789", "1234567890", "qwertyui", "qwertyuiop",
+    "iloveyou", "letmein1", "abcd1234", "11111111", "00000000",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn convert_to_string(&self) -> String {
+        match self {
+            Severity::Low => String::from("low"),
+            Severity::Medium => String::from("medium"),
+            Severity::High => String::from("high"),
+            Severity::Critical => String::from("critical"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PassphraseStrength {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl PassphraseStrength {
+    pub fn convert_to_string(&self) -> String {
+        match self {
+            PassphraseStrength::VeryWeak => String::from("very_weak"),
+            PassphraseStrength::Weak => String::from("weak"),
+            PassphraseStrength::Fair => String::from("fair"),
+            PassphraseStrength::Strong => String::from("strong"),
+        }
+    }
+}
+
+/// Rates a WPA passphrase by the size of the character pool it draws from and its length.
+/// Common and patterned passphrases are rated very weak regardless.
+pub fn rate_passphrase(passphrase: &str) -> PassphraseStrength {
+    // A raw PSK is almost always random
+    if passphrase.len() == 64 && passphrase.chars().all(|character| character.is_ascii_hexdigit()) {
+        return PassphraseStrength::Strong;
+    }
+
+    let lowered = passphrase.to_ascii_lowercase();
+    if COMMON_PASSPHRASES.contains(&lowered.as_str()) || is_patterned(passphrase) {
+        return PassphraseStrength::VeryWeak;
+    }
+
+    let character_classes = [
+        (passphrase.chars().any(|character| character.is_ascii_lowercase()), 26),
+        (passphrase.chars().any(|character| character.is_ascii_uppercase()), 26),
+        (passphrase.chars().any(|character| character.is_ascii_digit()), 10),
+        (passphrase.chars().any(|character| !character.is_ascii_alphanumeric()), 33),
+    ];
+    let pool: u32 = character_classes.iter().filter(|(present, _)| *present).map(|(_, size)| size).sum();
+
+    let bits = passphrase.chars().count() as f64 * (pool.max(1) as f64).log2();
+    match bits {
+        bits if bits < 40.0 => PassphraseStrength::Weak,
+        bits if bits < 60.0 => PassphraseStrength::Fair,
+        _ => PassphraseStrength::Strong,
+    }
+}
+
+/// Few distinct characters, or a straight run like "abcdefgh" or "87654321".
+fn is_patterned(passphrase: &str) -> bool {
+    let bytes = passphrase.as_bytes();
+
+    let mut distinct = bytes.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() <= 3 {
+        return true;
+    }
+
+    let steps: Vec<i16> = bytes.windows(2).map(|pair| pair[1] as i16 - pair[0] as i16).collect();
+    steps.iter().all(|step| *step == 1) || steps.iter().all(|step| *step == -1)
+}
+
+pub fn is_default_ssid(ssid: &str) -> bool {
+    let lowered = ssid.to_ascii_lowercase();
+    DEFAULT_SSIDS.contains(&lowered.as_str()) || DEFAULT_SSID_PREFIXES.iter().any(|prefix| lowered.starts_with(prefix))
+}
+
+#[derive(Debug, Clone)]
+pub struct SecurityFinding {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+    pub recommendation: &'static str,
+}
+
+impl SecurityFinding {
+    fn new(code: &'static str, severity: Severity, summary: &'static str, recommendation: &'static str) -> Self {
+        SecurityFinding { code, severity, summary, recommendation }
+    }
+
+    fn open() -> Self {
+        SecurityFinding::new("open", Severity::High,
+            "Traffic On This Network Is Not Encrypted",
+            "Avoid Signing In Or Sending Personal Information Here, Or Use A VPN")
+    }
+
+    fn wep() -> Self {
+        SecurityFinding::new("wep", Severity::Critical,
+            "WEP Can Be Cracked In Minutes",
+            "Only Join For Legacy Devices That Support Nothing Else. Move The Access Point To WPA2 Or WPA3")
+    }
+
+    fn wpa1() -> Self {
+        SecurityFinding::new("wpa1", Severity::High,
+            "Original WPA Is Deprecated And Has Known Weaknesses",
+            "Switch The Access Point To WPA2 Or WPA3")
+    }
+
+    fn tkip_only() -> Self {
+        SecurityFinding::new("tkip_only", Severity::High,
+            "TKIP Is Deprecated And Limits The Network To 54 Mbps",
+            "Enable AES (CCMP) On The Access Point")
+    }
+
+    fn default_ssid() -> Self {
+        SecurityFinding::new("default_ssid", Severity::Low,
+            "The Network Name Looks Like A Factory Default",
+            "Routers Left On Their Default Name Often Keep Their Default Admin Password Too. Change Both")
+    }
+
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+        dictionary.set("code", self.code);
+        dictionary.set("severity", self.severity.convert_to_string().as_str());
+        dictionary.set("summary", self.summary);
+        dictionary.set("recommendation", self.recommendation);
+
+        dictionary
+    }
+}
+
+pub struct SecurityReport {
+    pub name: String,
+    /// "network" for a scan result, "profile" for a saved profile.
+    pub kind: &'static str,
+    pub findings: Vec<SecurityFinding>,
+}
+
+impl SecurityReport {
+    /// The worst severity among the findings, or "secure" when there are none.
+    pub fn get_rating(&self) -> String {
+        self.findings.iter()
+            .map(|finding| finding.severity)
+            .max()
+            .map_or(String::from("secure"), |severity| severity.convert_to_string())
+    }
+
+    pub fn findings_to_array(&self) -> Array<Dictionary> {
+        let mut array = Array::new();
+        for finding in &self.findings {
+            array.push(&finding.to_dictionary());
+        }
+
+        array
+    }
+
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+        dictionary.set("name", self.name.as_str());
+        dictionary.set("kind", self.kind);
+        dictionary.set("rating", self.get_rating().as_str());
+        dictionary.set("findings", self.findings_to_array());
+
+        dictionary
+    }
+}
+
+/// Rates a scan result. `bss_entries` is every access point from the scan. Only the ones
+/// broadcasting this network's SSID are looked at, for the details the network list doesn't have.
+pub fn analyze_network(network: &Network, bss_entries: &[BssEntry]) -> SecurityReport {
+    let ssid = network.get_ssid().to_string();
+    let rsn_entries: Vec<_> = bss_entries.iter()
+        .filter(|entry| entry.ssid == ssid)
+        .filter_map(|entry| entry.rsn.as_ref())
+        .collect();
+    let wpa1_only = bss_entries.iter().any(|entry| entry.ssid == ssid && entry.wpa && entry.rsn.is_none());
+
+    let mut findings = Vec::new();
+    let security = network.get_security();
+    let encryption = network.get_encryption();
+
+    if network.is_insecure() {
+        findings.push(SecurityFinding::wep());
+    } else if !network.get_secured() {
+        findings.push(SecurityFinding::open());
+    }
+
+    if matches!(security, NetworkSecurity::WPA | NetworkSecurity::WPAPSK) || wpa1_only {
+        findings.push(SecurityFinding::wpa1());
+    }
+
+    let tkip_only = !rsn_entries.is_empty() && rsn_entries.iter().all(|rsn| rsn.is_tkip_only());
+    if matches!(encryption, EncryptionAlgorithm::TKIP) || tkip_only {
+        findings.push(SecurityFinding::tkip_only());
+    } else if rsn_entries.iter().any(|rsn| matches!(rsn.group_cipher, Some(CIPHER_SUITE_TKIP | CIPHER_SUITE_WEP40 | CIPHER_SUITE_WEP104))) {
+        findings.push(SecurityFinding::new("legacy_group_cipher", Severity::Low,
+            "Broadcast Traffic Uses An Older Cipher To Support Legacy Devices",
+            "Turn Off WPA/TKIP Compatibility Mode On The Access Point"));
+    }
+
+    // Without an RSN element there's no telling either way
+    let is_wpa2 = matches!(security, NetworkSecurity::WPA2 | NetworkSecurity::WPA2PSK);
+    if is_wpa2 && !rsn_entries.is_empty() && rsn_entries.iter().all(|rsn| !rsn.pmf_capable) {
+        findings.push(SecurityFinding::new("no_pmf", Severity::Medium,
+            "Management Frames Are Unprotected, So Anyone In Range Can Force Devices Off This Network",
+            "Enable Protected Management Frames (802.11w) On The Access Point"));
+    }
+
+    if is_default_ssid(&ssid) {
+        findings.push(SecurityFinding::default_ssid());
+    }
+
+    SecurityReport { name: ssid, kind: "network", findings }
+}
+
+/// Rates a saved profile from its XML. `passphrase_strength` is only known for profiles we created,
+/// since Windows won't hand back a key that isn't ours.
+pub fn analyze_profile(profile_name: &str, profile_xml: &str, passphrase_strength: Option<PassphraseStrength>) -> SecurityReport {
+    let authentication = read_profile_element(profile_xml, "authentication").unwrap_or_default();
+    let encryption = read_profile_element(profile_xml, "encryption").unwrap_or_default();
+
+    let mut findings = Vec::new();
+    match encryption.to_ascii_lowercase().as_str() {
+        "none" if authentication.eq_ignore_ascii_case("open") => findings.push(SecurityFinding::open()),
+        "wep" => findings.push(SecurityFinding::wep()),
+        "tkip" => findings.push(SecurityFinding::tkip_only()),
+        _ => {},
+    }
+
+    if authentication == "WPA" || authentication == "WPAPSK" {
+        findings.push(SecurityFinding::wpa1());
+    }
+
+    if is_default_ssid(profile_name) {
+        findings.push(SecurityFinding::default_ssid());
+    }
+
+    let weak_passphrase = match passphrase_strength {
+        Some(PassphraseStrength::VeryWeak) => Some(Severity::High),
+        Some(PassphraseStrength::Weak) => Some(Severity::Medium),
+        Some(PassphraseStrength::Fair) => Some(Severity::Low),
+        Some(PassphraseStrength::Strong) | None => None,
+    };
+    if let Some(severity) = weak_passphrase {
+        findings.push(SecurityFinding::new("weak_passphrase", severity,
+            "The Passphrase Could Be Guessed By Anyone Who Captures A Login",
+            "Use At Least 12 Random Characters, Or A Phrase Of Several Unrelated Words"));
+    }
+
+    SecurityReport { name: profile_name.to_string(), kind: "profile", findings }
+}
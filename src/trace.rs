@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::event_bus::InterfaceNotification;
+use crate::networking::Network;
+use crate::networking::scanning::BssEntry;
+use crate::windows_api::convert_guid_to_string;
+use crate::wlan_enums::{EncryptionAlgorithm, NetworkSecurity, RawNotification};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TraceNetwork {
+    pub ssid: String,
+    /// Missing from traces recorded before it was added, `ssid` is used then.
+    #[serde(default)]
+    pub ssid_bytes: Option<Vec<u8>>,
+    pub secured: bool,
+    pub connected: bool,
+    pub security: NetworkSecurity,
+    pub encryption: EncryptionAlgorithm,
+    pub bars: u32,
+    #[serde(default)]
+    pub signal_quality: u32,
+}
+
+impl TraceNetwork {
+    pub fn from_network(network: &Network) -> Self {
+        Self {
+            ssid: network.get_ssid().to_string(),
+            ssid_bytes: Some(network.get_ssid_bytes().to_vec()),
+            secured: network.secured,
+            connected: network.connected,
+            security: network.get_security(),
+            encryption: network.get_encryption(),
+            bars: network.bars,
+            signal_quality: network.signal_quality,
+        }
+    }
+
+    pub fn into_network(self) -> Network {
+        let ssid_bytes = self.ssid_bytes.unwrap_or_else(|| self.ssid.into_bytes());
+        Network::new(ssid_bytes, self.secured, self.connected, self.security, self.encryption, self.bars, self.signal_quality)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// `decoded` is only there for people reading the trace. Replay decodes `raw` again.
+    Notification { interface: String, name: String, decoded: String, raw: RawNotification },
+    /// `bss_entries` is missing from traces recorded before it was added.
+    ScanResult {
+        interface: String,
+        networks: Vec<TraceNetwork>,
+        #[serde(default)]
+        bss_entries: Vec<BssEntry>,
+    },
+    Connect { interface: String, ssid: String },
+    Disconnect { interface: String },
+}
+
+impl TraceEvent {
+    pub fn from_notification(event: &InterfaceNotification) -> Self {
+        TraceEvent::Notification {
+            interface: convert_guid_to_string(&event.guid),
+            name: event.notification.get_name().to_string(),
+            decoded: format!("{:?}", event.notification),
+            raw: event.raw.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// Milliseconds since recording started.
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+/// Writes trace events to a JSON lines file, one record per line.
+pub struct TraceRecorder {
+    writer: BufWriter<File>,
+    started_at: Instant,
+}
+
+impl TraceRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            started_at: Instant::now(),
+        })
+    }
+
+    /// `at` is when the event happened, which for notifications is before they are handled.
+    /// Anything from before the recording started is stamped 0.
+    pub fn record(&mut self, event: TraceEvent, at: Instant) -> io::Result<()> {
+        let record = TraceRecord {
+            timestamp_ms: at.saturating_duration_since(self.started_at).as_millis() as u64,
+            event,
+        };
+
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+
+        // Flush every record so a crash still leaves a usable trace behind
+        self.writer.flush()
+    }
+}
+
+/// Hands back recorded events once their timestamp comes due.
+pub struct TracePlayer {
+    records: VecDeque<TraceRecord>,
+    started_at: Instant,
+    speed: f64,
+}
+
+impl TracePlayer {
+    /// `speed` scales the trace clock. 1.0 is real time, 0.0 or less replays everything at once.
+    pub fn load(path: &Path, speed: f64) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = VecDeque::new();
+
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: TraceRecord = serde_json::from_str(&line).map_err(|error| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("Line {}: {}", line_number + 1, error))
+            })?;
+            records.push_back(record);
+        }
+
+        Ok(Self {
+            records,
+            started_at: Instant::now(),
+            speed,
+        })
+    }
+
+    pub fn next_due(&mut self) -> Option<TraceEvent> {
+        let next = self.records.front()?;
+
+        if self.speed > 0.0 {
+            let trace_clock_ms = self.started_at.elapsed().as_secs_f64() * 1000.0 * self.speed;
+            if (next.timestamp_ms as f64) > trace_clock_ms {
+                return None;
+            }
+        }
+
+        self.records.pop_front().map(|record| record.event)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.records.is_empty()
+    }
+}
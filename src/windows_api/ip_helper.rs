@@ -0,0 +1,174 @@
+use std::ffi::c_void;
+use std::mem::size_of;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use windows::core::GUID;
+use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, WIN32_ERROR};
+use windows::Win32::NetworkManagement::IpHelper::*;
+use windows::Win32::NetworkManagement::Ndis::NET_LUID_LH;
+use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6, SOCKET_ADDRESS};
+
+use crate::utils::*;
+
+pub struct AdapterAddresses {
+    pub adapter_name: String,
+    pub unicast_addresses: Vec<IpAddr>,
+    pub gateway_addresses: Vec<IpAddr>,
+    pub dns_server_addresses: Vec<IpAddr>,
+}
+
+pub fn get_adapter_addresses() -> Result<Vec<AdapterAddresses>, WIN32_ERROR> {
+    let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+    let mut buffer_size = 16 * 1024u32;
+
+    // u64 backing keeps the list aligned for IP_ADAPTER_ADDRESSES_LH
+    let mut buffer: Vec<u64>;
+    loop {
+        buffer = vec![0u64; (buffer_size as usize / size_of::<u64>()) + 1];
+
+        let result = unsafe {
+            GetAdaptersAddresses
+            (
+                AF_UNSPEC.0 as u32,
+                flags,
+                None,
+                Some(buffer.as_mut_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>()),
+                &mut buffer_size
+            )
+        };
+
+        if result == ERROR_BUFFER_OVERFLOW.0 {
+            continue;
+        }
+
+        check_win32(result)?;
+        break;
+    }
+
+    let mut adapters = Vec::new();
+    let mut adapter_ptr = buffer.as_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+
+    unsafe {
+        while !adapter_ptr.is_null() {
+            let adapter = &*adapter_ptr;
+
+            let mut unicast_addresses = Vec::new();
+            let mut unicast_ptr = adapter.FirstUnicastAddress;
+            while !unicast_ptr.is_null() {
+                if let Some(address) = socket_address_to_ip(&(*unicast_ptr).Address) {
+                    unicast_addresses.push(address);
+                }
+                unicast_ptr = (*unicast_ptr).Next;
+            }
+
+            let mut gateway_addresses = Vec::new();
+            let mut gateway_ptr = adapter.FirstGatewayAddress;
+            while !gateway_ptr.is_null() {
+                if let Some(address) = socket_address_to_ip(&(*gateway_ptr).Address) {
+                    gateway_addresses.push(address);
+                }
+                gateway_ptr = (*gateway_ptr).Next;
+            }
+
+            let mut dns_server_addresses = Vec::new();
+            let mut dns_ptr = adapter.FirstDnsServerAddress;
+            while !dns_ptr.is_null() {
+                if let Some(address) = socket_address_to_ip(&(*dns_ptr).Address) {
+                    dns_server_addresses.push(address);
+                }
+                dns_ptr = (*dns_ptr).Next;
+            }
+
+            adapters.push(AdapterAddresses {
+                adapter_name: adapter.AdapterName.to_string().unwrap_or_default(),
+                unicast_addresses,
+                gateway_addresses,
+                dns_server_addresses,
+            });
+
+            adapter_ptr = adapter.Next;
+        }
+    }
+
+    Ok(adapters)
+}
+
+unsafe fn socket_address_to_ip(address: &SOCKET_ADDRESS) -> Option<IpAddr> {
+    if address.lpSockaddr.is_null() {
+        return None;
+    }
+
+    unsafe {
+        match (*address.lpSockaddr).sa_family {
+            AF_INET => {
+                let sockaddr = &*(address.lpSockaddr as *const SOCKADDR_IN);
+                let bytes = sockaddr.sin_addr.S_un.S_un_b;
+                Some(IpAddr::V4(Ipv4Addr::new(bytes.s_b1, bytes.s_b2, bytes.s_b3, bytes.s_b4)))
+            }
+            AF_INET6 => {
+                let sockaddr = &*(address.lpSockaddr as *const SOCKADDR_IN6);
+                Some(IpAddr::V6(Ipv6Addr::from(sockaddr.sin6_addr.u.Byte)))
+            }
+            _ => None
+        }
+    }
+}
+
+/// Sends a single ICMP echo and returns the round trip time in milliseconds.
+pub fn icmp_echo(address: Ipv4Addr, timeout_ms: u32) -> Result<Option<u32>, WIN32_ERROR> {
+    let request_data = *b"xpwm-diagnostics";
+    let reply_size = size_of::<ICMP_ECHO_REPLY>() + request_data.len() + 8;
+    let mut reply_buffer = vec![0u64; (reply_size / size_of::<u64>()) + 1];
+
+    let icmp_handle = match unsafe { IcmpCreateFile() } {
+        Ok(handle) => handle,
+        Err(error) => return Err(WIN32_ERROR::from_error(&error).unwrap_or_default()),
+    };
+
+    let reply_count = unsafe {
+        IcmpSendEcho
+        (
+            icmp_handle,
+            u32::from_ne_bytes(address.octets()),
+            request_data.as_ptr() as *const c_void,
+            request_data.len() as u16,
+            None,
+            reply_buffer.as_mut_ptr() as *mut c_void,
+            reply_size as u32,
+            timeout_ms
+        )
+    };
+
+    let round_trip = if reply_count == 0 {
+        None
+    } else {
+        let reply = unsafe { &*(reply_buffer.as_ptr() as *const ICMP_ECHO_REPLY) };
+        if reply.Status == 0 {
+            Some(reply.RoundTripTime)
+        } else {
+            None
+        }
+    };
+
+    unsafe {
+        let _ = IcmpCloseHandle(icmp_handle);
+    }
+
+    Ok(round_trip)
+}
+
+/// Returns the (received, sent) byte counters for the interface with the given GUID.
+pub fn get_interface_octets(interface_guid: &GUID) -> Result<(u64, u64), WIN32_ERROR> {
+    let mut luid = NET_LUID_LH::default();
+    let result = unsafe { ConvertInterfaceGuidToLuid(interface_guid, &mut luid) };
+    check_win32(result.0)?;
+
+    let mut row = MIB_IF_ROW2 {
+        InterfaceLuid: luid,
+        ..Default::default()
+    };
+    let result = unsafe { GetIfEntry2(&mut row) };
+    check_win32(result.0)?;
+
+    Ok((row.InOctets, row.OutOctets))
+}
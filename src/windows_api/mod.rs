@@ -1,10 +1,12 @@
 pub mod wlan;
 pub mod enums;
+pub mod ip_helper;
+pub mod opcodes;
 
 use enums::MessageBoxResult;
 use godot::prelude::*;
 use widestring::U16CString;
-use windows::{core::PCWSTR, Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_ICONWARNING, MB_OK, MB_YESNO}};
+use windows::{core::{GUID, PCWSTR}, Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_ICONWARNING, MB_OK, MB_YESNO}};
 
 #[derive(GodotClass)]
 #[class(init, base=Object)]
@@ -109,6 +111,21 @@ pub fn convert_u16_slice_to_string(u16_slice: &[u16]) -> String {
     rust_string
 }
 
+pub fn convert_guid_to_string(guid: &GUID) -> String {
+    format!("{:?}", guid)
+}
+
+pub fn convert_string_to_guid(string: &str) -> Option<GUID> {
+    let trimmed = string.trim_start_matches('{').trim_end_matches('}');
+    match GUID::try_from(trimmed) {
+        Ok(guid) => Some(guid),
+        Err(error) => {
+            godot_error!("[SYSTEM] Failed To Parse GUID '{}': {}", string, error);
+            None
+        },
+    }
+}
+
 // pub fn convert_str_to_u16cstring(str: &str) -> Option<U16CString> {
 //     let string_u16 = match U16CString::from_str(str) {
 //         Ok(u16_cstring) => u16_cstring,
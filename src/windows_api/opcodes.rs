@@ -0,0 +1,265 @@
+use windows::core::{BOOL, GUID};
+use windows::Win32::Foundation::{HANDLE, WIN32_ERROR};
+use windows::Win32::NetworkManagement::WiFi::*;
+
+use super::wlan::{self, WlanBuffer, WlanError};
+
+/// An interface opcode for `WlanQueryInterface`. `Raw` is the type the OS returns and
+/// `Value` is what callers get back once the OS buffer has been read.
+pub trait InterfaceOpcode {
+    const OPCODE: WLAN_INTF_OPCODE;
+    type Raw;
+    type Value;
+
+    fn decode(buffer: WlanBuffer<Self::Raw>) -> Self::Value;
+}
+
+/// An interface opcode that can also be passed to `WlanSetInterface`.
+pub trait WritableOpcode: InterfaceOpcode {
+    type Input;
+    type RawInput;
+
+    fn encode(value: Self::Input) -> Self::RawInput;
+}
+
+pub fn query<O: InterfaceOpcode>(client_handle: HANDLE, interface_guid: &GUID) -> Result<O::Value, WlanError> {
+    let buffer = wlan::query_interface::<O::Raw>(client_handle, interface_guid, O::OPCODE)?;
+    Ok(O::decode(buffer))
+}
+
+pub fn set<O: WritableOpcode>(client_handle: HANDLE, interface_guid: &GUID, value: O::Input) -> Result<(), WIN32_ERROR> {
+    wlan::set_interface(client_handle, interface_guid, O::OPCODE, &O::encode(value))
+}
+
+pub struct AutoconfEnabled;
+
+impl InterfaceOpcode for AutoconfEnabled {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_autoconf_enabled;
+    type Raw = BOOL;
+    type Value = bool;
+
+    fn decode(buffer: WlanBuffer<BOOL>) -> bool {
+        buffer.as_bool()
+    }
+}
+
+impl WritableOpcode for AutoconfEnabled {
+    type Input = bool;
+    type RawInput = BOOL;
+
+    fn encode(value: bool) -> BOOL {
+        BOOL::from(value)
+    }
+}
+
+pub struct BackgroundScanEnabled;
+
+impl InterfaceOpcode for BackgroundScanEnabled {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_background_scan_enabled;
+    type Raw = BOOL;
+    type Value = bool;
+
+    fn decode(buffer: WlanBuffer<BOOL>) -> bool {
+        buffer.as_bool()
+    }
+}
+
+impl WritableOpcode for BackgroundScanEnabled {
+    type Input = bool;
+    type RawInput = BOOL;
+
+    fn encode(value: bool) -> BOOL {
+        BOOL::from(value)
+    }
+}
+
+pub struct MediaStreamingMode;
+
+impl InterfaceOpcode for MediaStreamingMode {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_media_streaming_mode;
+    type Raw = BOOL;
+    type Value = bool;
+
+    fn decode(buffer: WlanBuffer<BOOL>) -> bool {
+        buffer.as_bool()
+    }
+}
+
+impl WritableOpcode for MediaStreamingMode {
+    type Input = bool;
+    type RawInput = BOOL;
+
+    fn encode(value: bool) -> BOOL {
+        BOOL::from(value)
+    }
+}
+
+/// Reads every PHY's radio state. Writes go to a single PHY.
+pub struct RadioState;
+
+impl InterfaceOpcode for RadioState {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_radio_state;
+    type Raw = WLAN_RADIO_STATE;
+    type Value = Vec<WLAN_PHY_RADIO_STATE>;
+
+    fn decode(buffer: WlanBuffer<WLAN_RADIO_STATE>) -> Vec<WLAN_PHY_RADIO_STATE> {
+        let phy_count = (buffer.dwNumberOfPhys as usize).min(buffer.PhyRadioState.len());
+        buffer.PhyRadioState[..phy_count].to_vec()
+    }
+}
+
+impl WritableOpcode for RadioState {
+    type Input = WLAN_PHY_RADIO_STATE;
+    type RawInput = WLAN_PHY_RADIO_STATE;
+
+    fn encode(value: WLAN_PHY_RADIO_STATE) -> WLAN_PHY_RADIO_STATE {
+        value
+    }
+}
+
+pub struct BssType;
+
+impl InterfaceOpcode for BssType {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_bss_type;
+    type Raw = DOT11_BSS_TYPE;
+    type Value = DOT11_BSS_TYPE;
+
+    fn decode(buffer: WlanBuffer<DOT11_BSS_TYPE>) -> DOT11_BSS_TYPE {
+        *buffer
+    }
+}
+
+impl WritableOpcode for BssType {
+    type Input = DOT11_BSS_TYPE;
+    type RawInput = DOT11_BSS_TYPE;
+
+    fn encode(value: DOT11_BSS_TYPE) -> DOT11_BSS_TYPE {
+        value
+    }
+}
+
+pub struct InterfaceState;
+
+impl InterfaceOpcode for InterfaceState {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_interface_state;
+    type Raw = WLAN_INTERFACE_STATE;
+    type Value = WLAN_INTERFACE_STATE;
+
+    fn decode(buffer: WlanBuffer<WLAN_INTERFACE_STATE>) -> WLAN_INTERFACE_STATE {
+        *buffer
+    }
+}
+
+/// Fails with ERROR_INVALID_STATE while the interface is not connected.
+pub struct CurrentConnection;
+
+impl InterfaceOpcode for CurrentConnection {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_current_connection;
+    type Raw = WLAN_CONNECTION_ATTRIBUTES;
+    type Value = WlanBuffer<WLAN_CONNECTION_ATTRIBUTES>;
+
+    fn decode(buffer: WlanBuffer<WLAN_CONNECTION_ATTRIBUTES>) -> WlanBuffer<WLAN_CONNECTION_ATTRIBUTES> {
+        buffer
+    }
+}
+
+pub struct ChannelNumber;
+
+impl InterfaceOpcode for ChannelNumber {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_channel_number;
+    type Raw = u32;
+    type Value = u32;
+
+    fn decode(buffer: WlanBuffer<u32>) -> u32 {
+        *buffer
+    }
+}
+
+pub struct SupportedInfrastructureAuthCipherPairs;
+
+impl InterfaceOpcode for SupportedInfrastructureAuthCipherPairs {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_supported_infrastructure_auth_cipher_pairs;
+    type Raw = WLAN_AUTH_CIPHER_PAIR_LIST;
+    type Value = Vec<DOT11_AUTH_CIPHER_PAIR>;
+
+    fn decode(buffer: WlanBuffer<WLAN_AUTH_CIPHER_PAIR_LIST>) -> Vec<DOT11_AUTH_CIPHER_PAIR> {
+        buffer.items().to_vec()
+    }
+}
+
+pub struct SupportedAdhocAuthCipherPairs;
+
+impl InterfaceOpcode for SupportedAdhocAuthCipherPairs {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_supported_adhoc_auth_cipher_pairs;
+    type Raw = WLAN_AUTH_CIPHER_PAIR_LIST;
+    type Value = Vec<DOT11_AUTH_CIPHER_PAIR>;
+
+    fn decode(buffer: WlanBuffer<WLAN_AUTH_CIPHER_PAIR_LIST>) -> Vec<DOT11_AUTH_CIPHER_PAIR> {
+        buffer.items().to_vec()
+    }
+}
+
+/// Country or region codes such as "US", as reported by the driver.
+pub struct SupportedCountryOrRegionStrings;
+
+impl InterfaceOpcode for SupportedCountryOrRegionStrings {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_supported_country_or_region_string_list;
+    type Raw = WLAN_COUNTRY_OR_REGION_STRING_LIST;
+    type Value = Vec<String>;
+
+    fn decode(buffer: WlanBuffer<WLAN_COUNTRY_OR_REGION_STRING_LIST>) -> Vec<String> {
+        buffer.items()
+            .iter()
+            .map(|code| String::from_utf8_lossy(code).trim_end_matches(['\0', ' ']).to_string())
+            .collect()
+    }
+}
+
+pub struct SupportedSafeMode;
+
+impl InterfaceOpcode for SupportedSafeMode {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_supported_safe_mode;
+    type Raw = BOOL;
+    type Value = bool;
+
+    fn decode(buffer: WlanBuffer<BOOL>) -> bool {
+        buffer.as_bool()
+    }
+}
+
+pub struct CertifiedSafeMode;
+
+impl InterfaceOpcode for CertifiedSafeMode {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_certified_safe_mode;
+    type Raw = BOOL;
+    type Value = bool;
+
+    fn decode(buffer: WlanBuffer<BOOL>) -> bool {
+        buffer.as_bool()
+    }
+}
+
+pub struct Statistics;
+
+impl InterfaceOpcode for Statistics {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_statistics;
+    type Raw = WLAN_STATISTICS;
+    type Value = WlanBuffer<WLAN_STATISTICS>;
+
+    fn decode(buffer: WlanBuffer<WLAN_STATISTICS>) -> WlanBuffer<WLAN_STATISTICS> {
+        buffer
+    }
+}
+
+/// Received signal strength of the current connection in dBm.
+pub struct Rssi;
+
+impl InterfaceOpcode for Rssi {
+    const OPCODE: WLAN_INTF_OPCODE = wlan_intf_opcode_rssi;
+    type Raw = i32;
+    type Value = i32;
+
+    fn decode(buffer: WlanBuffer<i32>) -> i32 {
+        *buffer
+    }
+}
@@ -1,9 +1,11 @@
 use godot::prelude::*;
 use std::ffi::c_void;
-use std::mem::ManuallyDrop;
-use std::ptr::{null, null_mut};
+use std::mem::{offset_of, size_of};
+use std::ops::Deref;
+use std::ptr::{null_mut, NonNull};
+use std::slice;
 
-use widestring::{U16CString, U16String};
+use widestring::{U16CStr, U16CString, U16String};
 use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::NetworkManagement::WiFi::*;
 use windows::Win32::{Foundation::HANDLE, NetworkManagement::WiFi::WLAN_PROFILE_INFO_LIST};
@@ -30,15 +32,129 @@ pub enum WlanResult<T> {
     Error(WlanError)
 }
 
+/// Owns a buffer allocated by the WLAN service and frees it with `WlanFreeMemory` when dropped.
+pub struct WlanBuffer<T> {
+    ptr: NonNull<T>,
+    /// Size in bytes, for the calls that report one.
+    size: Option<u32>,
+}
+
+impl<T> WlanBuffer<T> {
+    /// `ptr` must come from a WLAN API call, must not be freed anywhere else
+    /// and must be at least `size` bytes long when a size is given.
+    unsafe fn from_raw(ptr: *mut T, size: Option<u32>) -> Result<Self, WlanError> {
+        match NonNull::new(ptr) {
+            Some(ptr) => Ok(Self { ptr, size }),
+            None => Err(WlanError::Error("[WLAN] WLAN API Returned A Null Buffer".to_string())),
+        }
+    }
+
+    /// Views the variable length array that starts `offset` bytes into the buffer,
+    /// clamped to the buffer size when it is known.
+    unsafe fn trailing_slice<E>(&self, offset: usize, count: u32) -> &[E] {
+        let mut count = count as usize;
+        if let Some(size) = self.size {
+            count = count.min((size as usize).saturating_sub(offset) / size_of::<E>());
+        }
+
+        unsafe {
+            slice::from_raw_parts(self.ptr.as_ptr().cast::<u8>().add(offset).cast::<E>(), count)
+        }
+    }
+}
+
+impl<T> Deref for WlanBuffer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Drop for WlanBuffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            WlanFreeMemory(self.ptr.as_ptr() as *const c_void);
+        }
+    }
+}
+
+impl WlanBuffer<WLAN_INTERFACE_INFO_LIST> {
+    pub fn items(&self) -> &[WLAN_INTERFACE_INFO] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_INTERFACE_INFO_LIST, InterfaceInfo), self.dwNumberOfItems) }
+    }
+}
+
+impl WlanBuffer<WLAN_PROFILE_INFO_LIST> {
+    pub fn items(&self) -> &[WLAN_PROFILE_INFO] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_PROFILE_INFO_LIST, ProfileInfo), self.dwNumberOfItems) }
+    }
+}
+
+impl WlanBuffer<WLAN_AVAILABLE_NETWORK_LIST> {
+    pub fn items(&self) -> &[WLAN_AVAILABLE_NETWORK] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_AVAILABLE_NETWORK_LIST, Network), self.dwNumberOfItems) }
+    }
+}
+
+impl WlanBuffer<WLAN_BSS_LIST> {
+    pub fn items(&self) -> &[WLAN_BSS_ENTRY] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_BSS_LIST, wlanBssEntries), self.dwNumberOfItems) }
+    }
+
+    /// The raw information elements of `entry`, which must come from `items()`. Empty when
+    /// they would run past the end of the buffer.
+    pub fn information_elements(&self, entry: &WLAN_BSS_ENTRY) -> &[u8] {
+        let buffer_start = self.ptr.as_ptr() as usize;
+        // ulIeOffset counts from the start of the entry, not the list
+        let start = entry as *const WLAN_BSS_ENTRY as usize + entry.ulIeOffset as usize;
+        let length = entry.ulIeSize as usize;
+
+        let in_bounds = self.size.is_some_and(|size| {
+            start >= buffer_start && start - buffer_start + length <= size as usize
+        });
+        if !in_bounds {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(start as *const u8, length) }
+    }
+}
+
+impl WlanBuffer<WLAN_STATISTICS> {
+    pub fn phy_counters(&self) -> &[WLAN_PHY_FRAME_STATISTICS] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_STATISTICS, PhyCounters), self.dwNumberOfPhys) }
+    }
+}
+
+impl WlanBuffer<WLAN_AUTH_CIPHER_PAIR_LIST> {
+    pub fn items(&self) -> &[DOT11_AUTH_CIPHER_PAIR] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_AUTH_CIPHER_PAIR_LIST, pAuthCipherPairList), self.dwNumberOfItems) }
+    }
+}
+
+impl WlanBuffer<WLAN_COUNTRY_OR_REGION_STRING_LIST> {
+    pub fn items(&self) -> &[[u8; 3]] {
+        unsafe { self.trailing_slice(offset_of!(WLAN_COUNTRY_OR_REGION_STRING_LIST, pCountryOrRegionStringList), self.dwNumberOfItems) }
+    }
+}
+
+impl WlanBuffer<u16> {
+    pub fn to_u16_string(&self) -> U16String {
+        unsafe { U16CStr::from_ptr_str(self.ptr.as_ptr()) }.to_ustring()
+    }
+}
+
 pub fn register_notification
 (
     client_handle: HANDLE, 
     source: WLAN_NOTIFICATION_SOURCES, 
     ignore_dupes: bool, 
     callback: Option<unsafe extern "system" fn(*mut L2_NOTIFICATION_DATA, *mut c_void)>,
+    context: *const c_void,
 ) {
     let result = unsafe {
-        WlanRegisterNotification(client_handle, source, ignore_dupes, callback, None, None, None)
+        WlanRegisterNotification(client_handle, source, ignore_dupes, callback, Some(context), None, None)
     };
 
     if let Err(error) = check_win32(result) {
@@ -59,7 +175,7 @@ pub fn scan(client_handle: HANDLE, interface_guid: &GUID) {
     }
 }
 
-pub fn enumerate_interfaces(client_handle: HANDLE) -> WlanResult<ManuallyDrop<Box<WLAN_INTERFACE_INFO_LIST>>> {
+pub fn enumerate_interfaces(client_handle: HANDLE) -> WlanResult<WlanBuffer<WLAN_INTERFACE_INFO_LIST>> {
     let mut interface_ptr: *mut WLAN_INTERFACE_INFO_LIST = null_mut();
     
     let result = unsafe {
@@ -68,19 +184,16 @@ pub fn enumerate_interfaces(client_handle: HANDLE) -> WlanResult<ManuallyDrop<Bo
 
     if result != 0 {
         return WlanResult::Error(WlanError::Win32Error(WIN32_ERROR(result)));
-    } else if interface_ptr.is_null() {
-        return WlanResult::Error(WlanError::Error("[SYSTEM] Interface Pointer Was Null".to_string()));
     }
 
-    let interface_box = unsafe {
-        ManuallyDrop::new(Box::from_raw(interface_ptr))
-    };
-
-    WlanResult::Value(interface_box)
+    match unsafe { WlanBuffer::from_raw(interface_ptr, None) } {
+        Ok(interface_list) => WlanResult::Value(interface_list),
+        Err(error) => WlanResult::Error(error),
+    }
 }
 
-pub fn set_interface(client_handle: HANDLE, interface_guid: &GUID, opcode: WLAN_INTF_OPCODE, data_size: u32) {
-    let data: *const c_void = null();
+pub fn set_interface<T>(client_handle: HANDLE, interface_guid: &GUID, opcode: WLAN_INTF_OPCODE, data: &T) -> Result<(), WIN32_ERROR> {
+    let data_ptr = data as *const T as *const c_void;
 
     let result = unsafe {
         WlanSetInterface
@@ -88,40 +201,76 @@ pub fn set_interface(client_handle: HANDLE, interface_guid: &GUID, opcode: WLAN_
             client_handle, 
             interface_guid, 
             opcode, 
-            data_size, 
-            data, 
+            size_of::<T>() as u32, 
+            data_ptr, 
             None
         )
     };
+
+    check_win32(result)
 }
 
-pub fn query_interface<'a>(
+/// `T` must be the type the OS returns for `opcode`. The buffer is checked to be at least that large.
+pub fn query_interface<T>(
     client_handle: HANDLE, 
-    interface_guid: &'a GUID, 
-    opcode: WLAN_INTF_OPCODE, 
-    size: &mut u32, 
-    opcode_type: &mut WLAN_OPCODE_VALUE_TYPE
-) -> Result<Option<&'a WLAN_CONNECTION_ATTRIBUTES>, WlanError> {
+    interface_guid: &GUID, 
+    opcode: WLAN_INTF_OPCODE
+) -> Result<WlanBuffer<T>, WlanError> {
+    let mut data_size = 0u32;
     let mut data_ptr: *mut c_void = null_mut();
+    let mut opcode_type = wlan_opcode_value_type_invalid;
     
     let result = unsafe {
-        WlanQueryInterface(client_handle, interface_guid, opcode, None, size, &mut data_ptr, Some(opcode_type))
+        WlanQueryInterface(client_handle, interface_guid, opcode, None, &mut data_size, &mut data_ptr, Some(&mut opcode_type))
     };
 
     if result != 0 {
         return Err(WlanError::Win32Error(WIN32_ERROR(result)));
     }
 
-    unsafe {
-        if data_ptr.is_null() {
-            return Err(WlanError::Error("[WLAN] Failed To Query Interface. Data Pointer Was Null.".to_string()));
-        } else {
-            return Ok(Some(&*(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES)));
-        }
+    let buffer = unsafe { WlanBuffer::from_raw(data_ptr.cast::<T>(), Some(data_size))? };
+    if (data_size as usize) < size_of::<T>() {
+        return Err(WlanError::Error(format!(
+            "[WLAN] Query Returned {} Bytes. Expected At Least {}.", data_size, size_of::<T>())));
+    }
+
+    Ok(buffer)
+}
+
+pub fn get_available_network_list(client_handle: HANDLE, interface_guid: &GUID) -> Result<WlanBuffer<WLAN_AVAILABLE_NETWORK_LIST>, WlanError> {
+    let mut list_ptr: *mut WLAN_AVAILABLE_NETWORK_LIST = null_mut();
+
+    let result = unsafe {
+        WlanGetAvailableNetworkList(client_handle, interface_guid, 0, None, &mut list_ptr)
+    };
+
+    if result != 0 {
+        return Err(WlanError::Win32Error(WIN32_ERROR(result)));
+    }
+
+    unsafe { WlanBuffer::from_raw(list_ptr, None) }
+}
+
+/// Every BSS the last scan saw, across all SSIDs.
+pub fn get_network_bss_list(client_handle: HANDLE, interface_guid: &GUID) -> Result<WlanBuffer<WLAN_BSS_LIST>, WlanError> {
+    let mut list_ptr: *mut WLAN_BSS_LIST = null_mut();
+
+    let result = unsafe {
+        WlanGetNetworkBssList(client_handle, interface_guid, None, dot11_BSS_type_any, false, None, &mut list_ptr)
+    };
+
+    if result != 0 {
+        return Err(WlanError::Win32Error(WIN32_ERROR(result)));
     }
+
+    let mut buffer = unsafe { WlanBuffer::from_raw(list_ptr, None)? };
+
+    // The list reports its own size, so items() can be clamped to it
+    buffer.size = Some(buffer.dwTotalSize);
+    Ok(buffer)
 }
 
-pub fn get_profile_list(client_handle: HANDLE, interface_guid: &GUID) -> Result<ManuallyDrop<Box<WLAN_PROFILE_INFO_LIST>>, WIN32_ERROR> {
+pub fn get_profile_list(client_handle: HANDLE, interface_guid: &GUID) -> Result<WlanBuffer<WLAN_PROFILE_INFO_LIST>, WlanError> {
     let mut list_ptr: *mut WLAN_PROFILE_INFO_LIST = null_mut();
 
     let result = unsafe {
@@ -135,17 +284,13 @@ pub fn get_profile_list(client_handle: HANDLE, interface_guid: &GUID) -> Result<
     };
 
     if result != 0 {
-        return Err(WIN32_ERROR(result));
+        return Err(WlanError::Win32Error(WIN32_ERROR(result)));
     }
 
-    let box_ptr = unsafe {
-        ManuallyDrop::new(Box::from_raw(list_ptr))
-    };
-
-    Ok(box_ptr)
+    unsafe { WlanBuffer::from_raw(list_ptr, None) }
 }
 
-pub fn delete_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name: &U16CString) {
+pub fn delete_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name: &U16CString) -> bool {
     let name_ptr = PCWSTR::from_raw(profile_name.as_ptr());
     let result = unsafe {
         WlanDeleteProfile
@@ -158,19 +303,20 @@ pub fn delete_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name
     };
 
     match check_win32(result) {
-        Ok(_) => godot_print!("[WLAN] Deleted Profile"),
+        Ok(_) => {
+            godot_print!("[WLAN] Deleted Profile");
+            true
+        },
         Err(error) => {
             godot_error!("[WLAN] Failed To Delete Profile: {:?}", error);
-            return;
+            false
         },
     }
 }
 
-pub fn get_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name: &U16CString) -> Result<U16String, WIN32_ERROR> {
-    let mut xml_u16string = U16CString::new();
-
+pub fn get_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name: &U16CString) -> Result<U16String, WlanError> {
     let profile_ptr = PCWSTR::from_raw(profile_name.as_ptr());
-    let mut xml_ptr = PWSTR::from_raw(xml_u16string.as_mut_ptr());
+    let mut xml_ptr = PWSTR::null();
 
     let result = unsafe {
         WlanGetProfile
@@ -186,11 +332,27 @@ pub fn get_profile(client_handle: HANDLE, interface_guid: &GUID, profile_name: &
     };
 
     if result != 0 {
-        return Err(WIN32_ERROR(result));
+        return Err(WlanError::Win32Error(WIN32_ERROR(result)));
     }
 
-    let xml_string = xml_u16string.to_ustring();
-    Ok(xml_string)
+    let xml_buffer = unsafe { WlanBuffer::from_raw(xml_ptr.0, None)? };
+    Ok(xml_buffer.to_u16_string())
+}
+
+/// Moves a profile in the interface's preference list. Position 0 is tried first.
+pub fn set_profile_position(client_handle: HANDLE, interface_guid: &GUID, profile_name: &U16CString, position: u32) -> Result<(), WIN32_ERROR> {
+    let result = unsafe {
+        WlanSetProfilePosition
+        (
+            client_handle, 
+            interface_guid, 
+            PCWSTR::from_raw(profile_name.as_ptr()), 
+            position, 
+            None
+        )
+    };
+
+    check_win32(result)
 }
 
 pub fn set_profile(client_handle: HANDLE, interface_guid: &GUID, profile: &U16CString, overwrite: bool) -> Result<(), WIN32_ERROR> {
@@ -1,7 +1,14 @@
+use std::ffi::c_void;
+use std::mem::{offset_of, size_of};
+use std::{ptr, slice};
+
 use godot::prelude::*;
+use serde::{Deserialize, Serialize};
 use windows::Win32::NetworkManagement::WiFi::*;
 
-#[derive(Clone, GodotConvert, Var, Export)]
+use crate::windows_api::convert_u16_slice_to_string;
+
+#[derive(Debug, Clone, GodotConvert, Var, Export, Serialize, Deserialize)]
 #[godot(via = GString)]
 pub enum NetworkSecurity {
     Open,
@@ -9,6 +16,12 @@ pub enum NetworkSecurity {
     WPA2,
     WPAPSK,
     WPA2PSK,
+    /// WPA3-Personal, which authenticates with the passphrase itself.
+    WPA3SAE,
+    /// Enhanced Open. Encrypted, but anyone can join without a key.
+    OWE,
+    /// WEP with shared key authentication. WEP with open authentication shows up as `Open`.
+    Shared,
     Unknown
 }
 
@@ -20,9 +33,26 @@ impl NetworkSecurity {
             NetworkSecurity::WPA2 => String::from("WPA2"),
             NetworkSecurity::WPAPSK => String::from("WPAPSK"),
             NetworkSecurity::WPA2PSK => String::from("WPA2PSK"),
+            NetworkSecurity::WPA3SAE => String::from("WPA3SAE"),
+            NetworkSecurity::OWE => String::from("OWE"),
+            NetworkSecurity::Shared => String::from("Shared"),
             NetworkSecurity::Unknown => String::from("Unknown"),
         }
     }
+
+    /// The profile XML authentication value.
+    pub fn get_profile_value(&self) -> &'static str {
+        match self {
+            NetworkSecurity::Open | NetworkSecurity::Unknown => "open",
+            NetworkSecurity::Shared => "shared",
+            NetworkSecurity::WPA => "WPA",
+            NetworkSecurity::WPA2 => "WPA2",
+            NetworkSecurity::WPAPSK => "WPAPSK",
+            NetworkSecurity::WPA2PSK => "WPA2PSK",
+            NetworkSecurity::WPA3SAE => "WPA3SAE",
+            NetworkSecurity::OWE => "OWE",
+        }
+    }
 }
 
 pub fn check_security(network: &WLAN_AVAILABLE_NETWORK) -> (bool, NetworkSecurity) {
@@ -30,21 +60,28 @@ pub fn check_security(network: &WLAN_AVAILABLE_NETWORK) -> (bool, NetworkSecurit
 
     let security_type = match network.dot11DefaultAuthAlgorithm {
         DOT11_AUTH_ALGO_80211_OPEN => NetworkSecurity::Open,
+        DOT11_AUTH_ALGO_80211_SHARED_KEY => NetworkSecurity::Shared,
         DOT11_AUTH_ALGO_WPA => NetworkSecurity::WPA,
         DOT11_AUTH_ALGO_WPA_PSK => NetworkSecurity::WPAPSK,
         DOT11_AUTH_ALGO_RSNA => NetworkSecurity::WPA2,
         DOT11_AUTH_ALGO_RSNA_PSK => NetworkSecurity::WPA2PSK,
+        DOT11_AUTH_ALGO_WPA3_SAE => NetworkSecurity::WPA3SAE,
+        DOT11_AUTH_ALGO_OWE => NetworkSecurity::OWE,
         _ => NetworkSecurity::Unknown
     };
 
     (is_secured, security_type)
 }
 
-#[derive(Clone, GodotConvert, Var, Export)]
+#[derive(Clone, GodotConvert, Var, Export, Serialize, Deserialize)]
 #[godot(via = GString)]
 pub enum EncryptionAlgorithm {
     AES,
     TKIP,
+    WEP40,
+    WEP104,
+    /// WEP where the scan didn't say which key size.
+    WEP,
     None
 }
 
@@ -53,15 +90,35 @@ impl EncryptionAlgorithm {
         match self {
             EncryptionAlgorithm::AES => String::from("AES"),
             EncryptionAlgorithm::TKIP => String::from("TKIP"),
+            EncryptionAlgorithm::WEP40 => String::from("WEP-40"),
+            EncryptionAlgorithm::WEP104 => String::from("WEP-104"),
+            EncryptionAlgorithm::WEP => String::from("WEP"),
             EncryptionAlgorithm::None => String::from("NONE"),
         }
     }
+
+    /// The profile XML encryption value. Windows doesn't distinguish WEP key sizes there.
+    pub fn get_profile_value(&self) -> &'static str {
+        match self {
+            EncryptionAlgorithm::AES => "AES",
+            EncryptionAlgorithm::TKIP => "TKIP",
+            EncryptionAlgorithm::WEP40 | EncryptionAlgorithm::WEP104 | EncryptionAlgorithm::WEP => "WEP",
+            EncryptionAlgorithm::None => "none",
+        }
+    }
+
+    pub fn is_wep(&self) -> bool {
+        matches!(self, EncryptionAlgorithm::WEP40 | EncryptionAlgorithm::WEP104 | EncryptionAlgorithm::WEP)
+    }
 }
 
 pub fn check_encryption(network: &WLAN_AVAILABLE_NETWORK) -> EncryptionAlgorithm     {
     let network_encryption = match network.dot11DefaultCipherAlgorithm {
         DOT11_CIPHER_ALGO_CCMP => EncryptionAlgorithm::AES,
         DOT11_CIPHER_ALGO_TKIP => EncryptionAlgorithm::TKIP,
+        DOT11_CIPHER_ALGO_WEP40 => EncryptionAlgorithm::WEP40,
+        DOT11_CIPHER_ALGO_WEP104 => EncryptionAlgorithm::WEP104,
+        DOT11_CIPHER_ALGO_WEP => EncryptionAlgorithm::WEP,
         DOT11_CIPHER_ALGO_NONE => EncryptionAlgorithm::None,
         _ => EncryptionAlgorithm::None
     };
@@ -69,48 +126,531 @@ pub fn check_encryption(network: &WLAN_AVAILABLE_NETWORK) -> EncryptionAlgorithm
     network_encryption
 }
 
-#[derive(Debug, Clone, GodotConvert, Var, Export)]
-#[godot(via = GString)]
+#[allow(non_upper_case_globals)]
+pub fn convert_phy_type_to_string(phy_type: DOT11_PHY_TYPE) -> String {
+    let phy_string = match phy_type {
+        dot11_phy_type_fhss => "802.11 FHSS",
+        dot11_phy_type_dsss => "802.11 DSSS",
+        dot11_phy_type_irbaseband => "802.11 IR",
+        dot11_phy_type_ofdm => "802.11a",
+        dot11_phy_type_hrdsss => "802.11b",
+        dot11_phy_type_erp => "802.11g",
+        dot11_phy_type_ht => "802.11n",
+        dot11_phy_type_vht => "802.11ac",
+        dot11_phy_type_dmg => "802.11ad",
+        dot11_phy_type_he => "802.11ax",
+        dot11_phy_type_eht => "802.11be",
+        _ => "Unknown"
+    };
+
+    String::from(phy_string)
+}
+
+pub fn convert_auth_algorithm_to_string(algorithm: DOT11_AUTH_ALGORITHM) -> String {
+    let auth_string = match algorithm {
+        DOT11_AUTH_ALGO_80211_OPEN => "Open",
+        DOT11_AUTH_ALGO_80211_SHARED_KEY => "Shared",
+        DOT11_AUTH_ALGO_WPA => "WPA-Enterprise",
+        DOT11_AUTH_ALGO_WPA_PSK => "WPA-Personal",
+        DOT11_AUTH_ALGO_WPA_NONE => "WPA-None",
+        DOT11_AUTH_ALGO_RSNA => "WPA2-Enterprise",
+        DOT11_AUTH_ALGO_RSNA_PSK => "WPA2-Personal",
+        DOT11_AUTH_ALGO_WPA3 => "WPA3-Enterprise 192",
+        DOT11_AUTH_ALGO_WPA3_SAE => "WPA3-Personal",
+        DOT11_AUTH_ALGO_OWE => "OWE",
+        DOT11_AUTH_ALGO_WPA3_ENT => "WPA3-Enterprise",
+        _ => "Unknown"
+    };
+
+    String::from(auth_string)
+}
+
+pub fn convert_cipher_algorithm_to_string(algorithm: DOT11_CIPHER_ALGORITHM) -> String {
+    let cipher_string = match algorithm {
+        DOT11_CIPHER_ALGO_NONE => "None",
+        DOT11_CIPHER_ALGO_WEP40 => "WEP-40",
+        DOT11_CIPHER_ALGO_WEP104 => "WEP-104",
+        DOT11_CIPHER_ALGO_WEP => "WEP",
+        DOT11_CIPHER_ALGO_TKIP => "TKIP",
+        DOT11_CIPHER_ALGO_CCMP => "CCMP",
+        DOT11_CIPHER_ALGO_CCMP_256 => "CCMP-256",
+        DOT11_CIPHER_ALGO_GCMP => "GCMP",
+        DOT11_CIPHER_ALGO_GCMP_256 => "GCMP-256",
+        DOT11_CIPHER_ALGO_BIP => "BIP",
+        DOT11_CIPHER_ALGO_WPA_USE_GROUP => "Group",
+        _ => "Unknown"
+    };
+
+    String::from(cipher_string)
+}
+
+#[allow(non_upper_case_globals)]
+pub fn convert_connection_mode_to_string(mode: WLAN_CONNECTION_MODE) -> String {
+    let mode_string = match mode {
+        wlan_connection_mode_profile => "Profile",
+        wlan_connection_mode_temporary_profile => "Temporary Profile",
+        wlan_connection_mode_discovery_secure => "Discovery (Secure)",
+        wlan_connection_mode_discovery_unsecure => "Discovery (Unsecure)",
+        wlan_connection_mode_auto => "Auto",
+        _ => "Invalid"
+    };
+
+    String::from(mode_string)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionNotificationData {
+    pub connection_mode: String,
+    pub profile_name: String,
+    pub ssid: String,
+    pub security_enabled: bool,
+    pub reason_code: u32,
+    pub flags: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MsmNotificationData {
+    pub connection_mode: String,
+    pub profile_name: String,
+    pub ssid: String,
+    pub bssid: [u8; 6],
+    pub security_enabled: bool,
+    pub first_peer: bool,
+    pub last_peer: bool,
+    pub reason_code: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RadioStateData {
+    pub phy_index: u32,
+    pub software_on: bool,
+    pub hardware_on: bool,
+}
+
+#[derive(Debug, Clone)]
 pub enum ConnectionNotifcation {
-    ConnectionStart,
-    ConnectionComplete,
-    ConnectionAttemptFail,
-    InvalidPassword,
-    Disconnected,
-    Unknown,
+    // ACM
+    AutoconfEnabled,
+    AutoconfDisabled,
+    BackgroundScanEnabled,
+    BackgroundScanDisabled,
+    BssTypeChange(i32),
+    PowerSettingChange(i32),
+    ScanComplete,
+    ScanFail(u32),
+    ConnectionStart(ConnectionNotificationData),
+    ConnectionComplete(ConnectionNotificationData),
+    ConnectionAttemptFail(ConnectionNotificationData),
+    FilterListChange,
+    InterfaceArrival,
+    InterfaceRemoval,
+    ProfileChange,
+    ProfileNameChange { old_name: String, new_name: String },
+    ProfilesExhausted,
+    NetworkNotAvailable,
+    NetworkAvailable,
+    Disconnecting(ConnectionNotificationData),
+    Disconnected(ConnectionNotificationData),
+    AdhocNetworkStateChange(i32),
+    ProfileUnblocked,
+    ProfileBlocked,
+    ScreenPowerChange(bool),
+    ScanListRefresh,
+    OperationalStateChange(u32),
+
+    // MSM
+    Associating(MsmNotificationData),
+    Associated(MsmNotificationData),
+    Authenticating(MsmNotificationData),
+    Connected(MsmNotificationData),
+    RoamingStart(MsmNotificationData),
+    RoamingEnd(MsmNotificationData),
+    RadioStateChange(RadioStateData),
+    SignalQualityChange(u32),
+    Disassociating(MsmNotificationData),
+    MsmDisconnected(MsmNotificationData),
+    PeerJoin(MsmNotificationData),
+    PeerLeave(MsmNotificationData),
+    AdapterRemoval(MsmNotificationData),
+    AdapterOperationModeChange(u32),
+    LinkDegraded,
+    LinkImproved,
+
+    Unknown { source: u32, code: u32 },
     Error
 }
 
+impl ConnectionNotifcation {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ConnectionNotifcation::AutoconfEnabled => "autoconf_enabled",
+            ConnectionNotifcation::AutoconfDisabled => "autoconf_disabled",
+            ConnectionNotifcation::BackgroundScanEnabled => "background_scan_enabled",
+            ConnectionNotifcation::BackgroundScanDisabled => "background_scan_disabled",
+            ConnectionNotifcation::BssTypeChange(_) => "bss_type_change",
+            ConnectionNotifcation::PowerSettingChange(_) => "power_setting_change",
+            ConnectionNotifcation::ScanComplete => "scan_complete",
+            ConnectionNotifcation::ScanFail(_) => "scan_fail",
+            ConnectionNotifcation::ConnectionStart(_) => "connection_start",
+            ConnectionNotifcation::ConnectionComplete(_) => "connection_complete",
+            ConnectionNotifcation::ConnectionAttemptFail(_) => "connection_attempt_fail",
+            ConnectionNotifcation::FilterListChange => "filter_list_change",
+            ConnectionNotifcation::InterfaceArrival => "interface_arrival",
+            ConnectionNotifcation::InterfaceRemoval => "interface_removal",
+            ConnectionNotifcation::ProfileChange => "profile_change",
+            ConnectionNotifcation::ProfileNameChange { .. } => "profile_name_change",
+            ConnectionNotifcation::ProfilesExhausted => "profiles_exhausted",
+            ConnectionNotifcation::NetworkNotAvailable => "network_not_available",
+            ConnectionNotifcation::NetworkAvailable => "network_available",
+            ConnectionNotifcation::Disconnecting(_) => "disconnecting",
+            ConnectionNotifcation::Disconnected(_) => "disconnected",
+            ConnectionNotifcation::AdhocNetworkStateChange(_) => "adhoc_network_state_change",
+            ConnectionNotifcation::ProfileUnblocked => "profile_unblocked",
+            ConnectionNotifcation::ProfileBlocked => "profile_blocked",
+            ConnectionNotifcation::ScreenPowerChange(_) => "screen_power_change",
+            ConnectionNotifcation::ScanListRefresh => "scan_list_refresh",
+            ConnectionNotifcation::OperationalStateChange(_) => "operational_state_change",
+            ConnectionNotifcation::Associating(_) => "associating",
+            ConnectionNotifcation::Associated(_) => "associated",
+            ConnectionNotifcation::Authenticating(_) => "authenticating",
+            ConnectionNotifcation::Connected(_) => "connected",
+            ConnectionNotifcation::RoamingStart(_) => "roaming_start",
+            ConnectionNotifcation::RoamingEnd(_) => "roaming_end",
+            ConnectionNotifcation::RadioStateChange(_) => "radio_state_change",
+            ConnectionNotifcation::SignalQualityChange(_) => "signal_quality_change",
+            ConnectionNotifcation::Disassociating(_) => "disassociating",
+            ConnectionNotifcation::MsmDisconnected(_) => "msm_disconnected",
+            ConnectionNotifcation::PeerJoin(_) => "peer_join",
+            ConnectionNotifcation::PeerLeave(_) => "peer_leave",
+            ConnectionNotifcation::AdapterRemoval(_) => "adapter_removal",
+            ConnectionNotifcation::AdapterOperationModeChange(_) => "adapter_operation_mode_change",
+            ConnectionNotifcation::LinkDegraded => "link_degraded",
+            ConnectionNotifcation::LinkImproved => "link_improved",
+            ConnectionNotifcation::Unknown { .. } => "unknown",
+            ConnectionNotifcation::Error => "error",
+        }
+    }
+
+    pub fn to_dictionary(&self) -> Dictionary {
+        let mut dictionary = Dictionary::new();
+
+        match self {
+            ConnectionNotifcation::ConnectionStart(data)
+            | ConnectionNotifcation::ConnectionComplete(data)
+            | ConnectionNotifcation::ConnectionAttemptFail(data)
+            | ConnectionNotifcation::Disconnecting(data)
+            | ConnectionNotifcation::Disconnected(data) => {
+                dictionary.set("connection_mode", data.connection_mode.as_str());
+                dictionary.set("profile_name", data.profile_name.as_str());
+                dictionary.set("ssid", data.ssid.as_str());
+                dictionary.set("security_enabled", data.security_enabled);
+                dictionary.set("reason_code", data.reason_code as i64);
+                dictionary.set("flags", data.flags as i64);
+            },
+            ConnectionNotifcation::Associating(data)
+            | ConnectionNotifcation::Associated(data)
+            | ConnectionNotifcation::Authenticating(data)
+            | ConnectionNotifcation::Connected(data)
+            | ConnectionNotifcation::RoamingStart(data)
+            | ConnectionNotifcation::RoamingEnd(data)
+            | ConnectionNotifcation::Disassociating(data)
+            | ConnectionNotifcation::MsmDisconnected(data)
+            | ConnectionNotifcation::PeerJoin(data)
+            | ConnectionNotifcation::PeerLeave(data)
+            | ConnectionNotifcation::AdapterRemoval(data) => {
+                dictionary.set("connection_mode", data.connection_mode.as_str());
+                dictionary.set("profile_name", data.profile_name.as_str());
+                dictionary.set("ssid", data.ssid.as_str());
+                dictionary.set("bssid", convert_bssid_to_string(&data.bssid).as_str());
+                dictionary.set("security_enabled", data.security_enabled);
+                dictionary.set("first_peer", data.first_peer);
+                dictionary.set("last_peer", data.last_peer);
+                dictionary.set("reason_code", data.reason_code as i64);
+            },
+            ConnectionNotifcation::RadioStateChange(data) => {
+                dictionary.set("phy_index", data.phy_index as i64);
+                dictionary.set("software_on", data.software_on);
+                dictionary.set("hardware_on", data.hardware_on);
+            },
+            ConnectionNotifcation::ProfileNameChange { old_name, new_name } => {
+                dictionary.set("old_name", old_name.as_str());
+                dictionary.set("new_name", new_name.as_str());
+            },
+            ConnectionNotifcation::BssTypeChange(value)
+            | ConnectionNotifcation::PowerSettingChange(value)
+            | ConnectionNotifcation::AdhocNetworkStateChange(value) => {
+                dictionary.set("value", *value as i64);
+            },
+            ConnectionNotifcation::ScanFail(reason_code) => {
+                dictionary.set("reason_code", *reason_code as i64);
+            },
+            ConnectionNotifcation::SignalQualityChange(quality) => {
+                dictionary.set("signal_quality", *quality as i64);
+            },
+            ConnectionNotifcation::OperationalStateChange(value)
+            | ConnectionNotifcation::AdapterOperationModeChange(value) => {
+                dictionary.set("value", *value as i64);
+            },
+            ConnectionNotifcation::ScreenPowerChange(on) => {
+                dictionary.set("on", *on);
+            },
+            ConnectionNotifcation::Unknown { source, code } => {
+                dictionary.set("source", *source as i64);
+                dictionary.set("code", *code as i64);
+            },
+            _ => {}
+        }
+
+        dictionary
+    }
+
+    /// The reason code the OS attached to a connection failure, if any.
+    pub fn get_reason_code(&self) -> Option<u32> {
+        match self {
+            ConnectionNotifcation::ConnectionAttemptFail(data)
+            | ConnectionNotifcation::ConnectionComplete(data)
+            | ConnectionNotifcation::Disconnected(data) => Some(data.reason_code),
+            ConnectionNotifcation::MsmDisconnected(data) => Some(data.reason_code),
+            ConnectionNotifcation::ScanFail(reason_code) => Some(*reason_code),
+            _ => None
+        }
+    }
+
+    /// A disconnect the OS didn't report as a normal, requested one. Link loss, AP deauth and the like.
+    pub fn is_unexpected_disconnect(&self) -> bool {
+        match self {
+            ConnectionNotifcation::Disconnected(data) => {
+                !matches!(data.reason_code, WLAN_REASON_CODE_SUCCESS | WLAN_REASON_CODE_USER_CANCELLED)
+            },
+            _ => false
+        }
+    }
+
+    pub fn is_invalid_password(&self) -> bool {
+        matches!(
+            self.get_reason_code(),
+            Some(WLAN_REASON_CODE_MSMSEC_PSK_MISMATCH_SUSPECTED) | Some(WLAN_REASON_CODE_KEY_MISMATCH)
+        )
+    }
+}
+
+pub fn convert_bssid_to_string(bssid: &[u8; 6]) -> String {
+    bssid.iter().map(|b| format!("{:02X}", b)).collect::<Vec<String>>().join(":")
+}
+
 impl Default for NetworkSecurity {
     fn default() -> NetworkSecurity {
         NetworkSecurity::Open
     }
 }
 
+/// An undecoded notification with its payload copied out of the OS buffer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RawNotification {
+    pub source: u32,
+    pub code: u32,
+    pub data: Vec<u8>,
+}
+
+impl RawNotification {
+    pub unsafe fn from_notification(notification: &L2_NOTIFICATION_DATA) -> Self {
+        let data = if notification.pData.is_null() || notification.dwDataSize == 0 {
+            Vec::new()
+        } else {
+            unsafe { slice::from_raw_parts(notification.pData as *const u8, notification.dwDataSize as usize).to_vec() }
+        };
+
+        Self {
+            source: notification.NotificationSource.0,
+            code: notification.NotificationCode,
+            data,
+        }
+    }
+
+    /// Decodes the payload. Returns None for sources other than ACM and MSM.
+    pub fn decode(&self) -> Option<ConnectionNotifcation> {
+        // The decoders read whole structs, so give them a buffer aligned like the OS one
+        let mut aligned = vec![0u64; self.data.len().div_ceil(size_of::<u64>())];
+        unsafe {
+            ptr::copy_nonoverlapping(self.data.as_ptr(), aligned.as_mut_ptr() as *mut u8, self.data.len());
+        }
+
+        let data = if self.data.is_empty() { ptr::null() } else { aligned.as_ptr() as *const c_void };
+        let data_size = self.data.len() as u32;
+
+        let notification = match self.source {
+            source if source == WLAN_NOTIFICATION_SOURCE_ACM.0 => unsafe {
+                convert_connection_notification(WLAN_NOTIFICATION_ACM(self.code as i32), data, data_size)
+            },
+            source if source == WLAN_NOTIFICATION_SOURCE_MSM.0 => unsafe {
+                convert_msm_notification(WLAN_NOTIFICATION_MSM(self.code as i32), data, data_size)
+            },
+            _ => return None
+        };
+
+        Some(notification)
+    }
+}
+
+unsafe fn read_payload<T: Copy>(data: *const c_void, data_size: u32) -> Option<T> {
+    if data.is_null() || (data_size as usize) < size_of::<T>() {
+        return None;
+    }
+
+    Some(unsafe { ptr::read_unaligned(data as *const T) })
+}
+
+fn convert_ssid_to_string(ssid: &DOT11_SSID) -> String {
+    let ssid_length = (ssid.uSSIDLength as usize).min(ssid.ucSSID.len());
+    String::from_utf8_lossy(&ssid.ucSSID[..ssid_length]).to_string()
+}
+
+unsafe fn read_connection_data(data: *const c_void, data_size: u32) -> ConnectionNotificationData {
+    // strProfileXml trails the fixed part, so only the fixed part has to be present
+    let fixed_size = offset_of!(WLAN_CONNECTION_NOTIFICATION_DATA, strProfileXml);
+    if data.is_null() || (data_size as usize) < fixed_size {
+        return ConnectionNotificationData::default();
+    }
+
+    let mut raw = WLAN_CONNECTION_NOTIFICATION_DATA::default();
+    let copy_size = (data_size as usize).min(size_of::<WLAN_CONNECTION_NOTIFICATION_DATA>());
+    unsafe {
+        ptr::copy_nonoverlapping(data as *const u8, &mut raw as *mut WLAN_CONNECTION_NOTIFICATION_DATA as *mut u8, copy_size);
+    }
+
+    ConnectionNotificationData {
+        connection_mode: convert_connection_mode_to_string(raw.wlanConnectionMode),
+        profile_name: convert_u16_slice_to_string(&raw.strProfileName),
+        ssid: convert_ssid_to_string(&raw.dot11Ssid),
+        security_enabled: raw.bSecurityEnabled.as_bool(),
+        reason_code: raw.wlanReasonCode,
+        flags: raw.dwFlags.0,
+    }
+}
+
+unsafe fn read_msm_data(data: *const c_void, data_size: u32) -> MsmNotificationData {
+    let Some(raw) = (unsafe { read_payload::<WLAN_MSM_NOTIFICATION_DATA>(data, data_size) }) else {
+        return MsmNotificationData::default();
+    };
+
+    MsmNotificationData {
+        connection_mode: convert_connection_mode_to_string(raw.wlanConnectionMode),
+        profile_name: convert_u16_slice_to_string(&raw.strProfileName),
+        ssid: convert_ssid_to_string(&raw.dot11Ssid),
+        bssid: raw.dot11MacAddr,
+        security_enabled: raw.bSecurityEnabled.as_bool(),
+        first_peer: raw.bFirstPeer.as_bool(),
+        last_peer: raw.bLastPeer.as_bool(),
+        reason_code: raw.wlanReasonCode,
+    }
+}
+
+unsafe fn read_profile_name_change(data: *const c_void, data_size: u32) -> (String, String) {
+    if data.is_null() {
+        return (String::new(), String::new());
+    }
+
+    // Two NULL terminated names packed back to back: old name, then new name
+    let wide = unsafe { slice::from_raw_parts(data as *const u16, data_size as usize / size_of::<u16>()) };
+    let mut names = wide.split(|&c| c == 0).map(String::from_utf16_lossy);
+
+    (names.next().unwrap_or_default(), names.next().unwrap_or_default())
+}
+
+#[allow(non_upper_case_globals)]
+pub unsafe fn convert_connection_notification(code: WLAN_NOTIFICATION_ACM, data: *const c_void, data_size: u32) -> ConnectionNotifcation {
+    let notif = unsafe {
+        match code {
+            wlan_notification_acm_autoconf_enabled => ConnectionNotifcation::AutoconfEnabled,
+            wlan_notification_acm_autoconf_disabled => ConnectionNotifcation::AutoconfDisabled,
+            wlan_notification_acm_background_scan_enabled => ConnectionNotifcation::BackgroundScanEnabled,
+            wlan_notification_acm_background_scan_disabled => ConnectionNotifcation::BackgroundScanDisabled,
+            wlan_notification_acm_bss_type_change => ConnectionNotifcation::BssTypeChange(read_payload::<i32>(data, data_size).unwrap_or_default()),
+            wlan_notification_acm_power_setting_change => ConnectionNotifcation::PowerSettingChange(read_payload::<i32>(data, data_size).unwrap_or_default()),
+            wlan_notification_acm_scan_complete => ConnectionNotifcation::ScanComplete,
+            wlan_notification_acm_scan_fail => ConnectionNotifcation::ScanFail(read_payload::<u32>(data, data_size).unwrap_or_default()),
+            wlan_notification_acm_connection_start => ConnectionNotifcation::ConnectionStart(read_connection_data(data, data_size)),
+            wlan_notification_acm_connection_complete => ConnectionNotifcation::ConnectionComplete(read_connection_data(data, data_size)),
+            wlan_notification_acm_connection_attempt_fail => ConnectionNotifcation::ConnectionAttemptFail(read_connection_data(data, data_size)),
+            wlan_notification_acm_filter_list_change => ConnectionNotifcation::FilterListChange,
+            wlan_notification_acm_interface_arrival => ConnectionNotifcation::InterfaceArrival,
+            wlan_notification_acm_interface_removal => ConnectionNotifcation::InterfaceRemoval,
+            wlan_notification_acm_profile_change => ConnectionNotifcation::ProfileChange,
+            wlan_notification_acm_profile_name_change => {
+                let (old_name, new_name) = read_profile_name_change(data, data_size);
+                ConnectionNotifcation::ProfileNameChange { old_name, new_name }
+            },
+            wlan_notification_acm_profiles_exhausted => ConnectionNotifcation::ProfilesExhausted,
+            wlan_notification_acm_network_not_available => ConnectionNotifcation::NetworkNotAvailable,
+            wlan_notification_acm_network_available => ConnectionNotifcation::NetworkAvailable,
+            wlan_notification_acm_disconnecting => ConnectionNotifcation::Disconnecting(read_connection_data(data, data_size)),
+            wlan_notification_acm_disconnected => ConnectionNotifcation::Disconnected(read_connection_data(data, data_size)),
+            wlan_notification_acm_adhoc_network_state_change => ConnectionNotifcation::AdhocNetworkStateChange(read_payload::<i32>(data, data_size).unwrap_or_default()),
+            wlan_notification_acm_profile_unblocked => ConnectionNotifcation::ProfileUnblocked,
+            wlan_notification_acm_profile_blocked => ConnectionNotifcation::ProfileBlocked,
+            wlan_notification_acm_screen_power_change => ConnectionNotifcation::ScreenPowerChange(read_payload::<i32>(data, data_size).unwrap_or_default() != 0),
+            wlan_notification_acm_scan_list_refresh => ConnectionNotifcation::ScanListRefresh,
+            wlan_notification_acm_operational_state_change => ConnectionNotifcation::OperationalStateChange(read_payload::<u32>(data, data_size).unwrap_or_default()),
+            _ => ConnectionNotifcation::Unknown { source: WLAN_NOTIFICATION_SOURCE_ACM.0, code: code.0 as u32 }
+        }
+    };
+
+    notif
+}
+
 #[allow(non_upper_case_globals)]
-pub fn convert_connection_notification(code: WLAN_NOTIFICATION_ACM) -> ConnectionNotifcation {
-    let notif = match code {
-        wlan_notification_acm_connection_start => ConnectionNotifcation::ConnectionStart,
-        wlan_notification_acm_connection_complete => ConnectionNotifcation::ConnectionComplete,
-        wlan_notification_acm_disconnected => ConnectionNotifcation::Disconnected,
-        wlan_notification_acm_connection_attempt_fail => ConnectionNotifcation::ConnectionAttemptFail,
-        _ => ConnectionNotifcation::Unknown
+pub unsafe fn convert_msm_notification(code: WLAN_NOTIFICATION_MSM, data: *const c_void, data_size: u32) -> ConnectionNotifcation {
+    let notif = unsafe {
+        match code {
+            wlan_notification_msm_associating => ConnectionNotifcation::Associating(read_msm_data(data, data_size)),
+            wlan_notification_msm_associated => ConnectionNotifcation::Associated(read_msm_data(data, data_size)),
+            wlan_notification_msm_authenticating => ConnectionNotifcation::Authenticating(read_msm_data(data, data_size)),
+            wlan_notification_msm_connected => ConnectionNotifcation::Connected(read_msm_data(data, data_size)),
+            wlan_notification_msm_roaming_start => ConnectionNotifcation::RoamingStart(read_msm_data(data, data_size)),
+            wlan_notification_msm_roaming_end => ConnectionNotifcation::RoamingEnd(read_msm_data(data, data_size)),
+            wlan_notification_msm_radio_state_change => {
+                let radio_state = read_payload::<WLAN_PHY_RADIO_STATE>(data, data_size).unwrap_or_default();
+                ConnectionNotifcation::RadioStateChange(RadioStateData {
+                    phy_index: radio_state.dwPhyIndex,
+                    software_on: radio_state.dot11SoftwareRadioState == dot11_radio_state_on,
+                    hardware_on: radio_state.dot11HardwareRadioState == dot11_radio_state_on,
+                })
+            },
+            wlan_notification_msm_signal_quality_change => ConnectionNotifcation::SignalQualityChange(read_payload::<u32>(data, data_size).unwrap_or_default()),
+            wlan_notification_msm_disassociating => ConnectionNotifcation::Disassociating(read_msm_data(data, data_size)),
+            wlan_notification_msm_disconnected => ConnectionNotifcation::MsmDisconnected(read_msm_data(data, data_size)),
+            wlan_notification_msm_peer_join => ConnectionNotifcation::PeerJoin(read_msm_data(data, data_size)),
+            wlan_notification_msm_peer_leave => ConnectionNotifcation::PeerLeave(read_msm_data(data, data_size)),
+            wlan_notification_msm_adapter_removal => ConnectionNotifcation::AdapterRemoval(read_msm_data(data, data_size)),
+            wlan_notification_msm_adapter_operation_mode_change => ConnectionNotifcation::AdapterOperationModeChange(read_payload::<u32>(data, data_size).unwrap_or_default()),
+            wlan_notification_msm_link_degraded => ConnectionNotifcation::LinkDegraded,
+            wlan_notification_msm_link_improved => ConnectionNotifcation::LinkImproved,
+            _ => ConnectionNotifcation::Unknown { source: WLAN_NOTIFICATION_SOURCE_MSM.0, code: code.0 as u32 }
+        }
     };
 
     notif
 }
 
-pub fn convert_msm_notification(notif_code: u32, reason_code: u32) -> ConnectionNotifcation {
-    match notif_code {
-        11 => match reason_code {
-            0x1003 => ConnectionNotifcation::InvalidPassword,
-            _ => ConnectionNotifcation::Unknown
-        },
-        _ => ConnectionNotifcation::Unknown
+/// Lifecycle of the `WlanAPI` singleton. Only `Ready` allows calls that touch an interface.
+#[derive(Debug, Clone, Copy, PartialEq, GodotConvert, Var, Export)]
+#[godot(via = GString)]
+pub enum WlanApiState {
+    Uninitialized,
+    Ready,
+    NoAdapter,
+    ServiceDown,
+    ShuttingDown,
+}
+
+impl Default for WlanApiState {
+    fn default() -> Self {
+        WlanApiState::Uninitialized
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, GodotConvert, Var, Export)]
+#[godot(via = GString)]
 pub enum WlanInterfaceState {
     Connected,
     Disconnected,
@@ -1,27 +1,87 @@
 use crate::globals;
-use crate::profile_management::generate_network_profile_xml;
-use crate::windows_api::{convert_string_to_u16cstring, convert_u16_slice_to_string, wlan};
+use crate::profile_management::{generate_network_profile_xml, ConnectionPolicy};
+use crate::windows_api::{convert_guid_to_string, convert_string_to_guid, convert_string_to_u16cstring, convert_u16_slice_to_string, wlan};
+use crate::event_pump::WlanEventPump;
+use crate::event_bus::{InterfaceNotification, OverflowPolicy, Subscription, DEFAULT_QUEUE_CAPACITY};
+use crate::trace::{TraceEvent, TraceNetwork, TracePlayer, TraceRecorder};
+use crate::windows_api::opcodes::{AutoconfEnabled, BackgroundScanEnabled, BssType, CertifiedSafeMode, MediaStreamingMode, SupportedAdhocAuthCipherPairs, SupportedCountryOrRegionStrings, SupportedInfrastructureAuthCipherPairs, SupportedSafeMode};
+use crate::known_networks::{KnownNetworkStore, DEFAULT_STORE_PATH};
+use crate::key_validation::{get_stored_key, validate_key, validation_to_dictionary, KeyFormat, KeyRequirement};
+use crate::auto_reconnect::{AutoReconnect, ReconnectStep, ReconnectTarget};
+use crate::roaming::RoamingAssistant;
+use crate::security_advisor::{analyze_network, analyze_profile, rate_passphrase, PassphraseStrength};
+use crate::network_selection::{CallablePolicy, Candidate, NetworkSelector, Selection, WeightedPolicy};
 use crate::networking::NetworkManager;
-use crate::wlan_enums::{ConnectionNotifcation, NetworkSecurity, NotificationState, WlanInterfaceState};
+use crate::networking::scanning::Band;
+use crate::networking::session::SessionEvent;
+use crate::networking::diagnostics::{LinkDiagnostics, ProbeResult};
+use crate::networking::statistics::{InterfaceStatistics, StatisticsRates};
+use crate::wlan_enums::{convert_auth_algorithm_to_string, convert_bssid_to_string, convert_cipher_algorithm_to_string, ConnectionNotifcation, EncryptionAlgorithm, NetworkSecurity, NotificationState, WlanApiState, WlanInterfaceState};
+use godot::classes::{Engine, ProjectSettings, SceneTree};
 use godot::prelude::*;
-use std::fs::{create_dir_all, File};
-use std::io::{Read, Write};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::ptr::null_mut;
-use std::sync::TryLockError::{Poisoned, WouldBlock};
-use std::sync::mpsc::TryRecvError::{Disconnected, Empty};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+use windows::core::GUID;
 use windows::Win32::Foundation::HANDLE;
+use windows::Win32::NetworkManagement::WiFi::{dot11_BSS_type_any, dot11_BSS_type_independent, dot11_BSS_type_infrastructure};
 
 #[derive(GodotClass)]
 #[class(base=Object)]
 pub struct WlanAPI {
     network_manager: NetworkManager,
+    state: WlanApiState,
     notif_state: NotificationState,
-    interface_state: WlanInterfaceState,
-    known_networks: Array<GString>,
+    notification_subscription: Subscription,
+    known_networks: KnownNetworkStore,
+    statistics_interval: Option<Duration>,
+    last_statistics_poll: Option<Instant>,
+    /// Report of the diagnostics run still in progress.
+    pending_diagnostics: Option<Receiver<LinkDiagnostics>>,
+    event_pump: Option<Gd<WlanEventPump>>,
+    event_pump_interval: f64,
+    recorder: Option<TraceRecorder>,
+    player: Option<TracePlayer>,
+    selector: NetworkSelector,
+    selection_weights: WeightedPolicy,
+    auto_select_enabled: bool,
+    /// Profile we asked to connect to that hasn't completed or failed yet.
+    pending_selection: Option<String>,
+    /// Interfaces `disconnect` was called on, so auto selection and reconnect don't undo it.
+    /// Cleared by the next connect on that interface, or when it connects again by any other means.
+    user_disconnected: HashSet<GUID>,
+    roaming: RoamingAssistant,
+    reconnect: AutoReconnect,
+    connection_attempt: Option<ConnectionAttempt>,
+    /// Profile `generate_profile` saved that didn't exist before, until a connect picks it up.
+    created_profile: Option<String>,
+    /// SSID of the attempt `cancel_connection` aborted, so its failure isn't reported again.
+    cancelled_attempt: Option<String>,
+    /// Left out of auto selection until something connects, so the Disconnected that follows
+    /// `cancel_connection` doesn't pick it straight back up.
+    cancelled_network: Option<String>,
+    /// Key slot written into WEP profiles.
+    wep_key_index: u32,
+    /// Save WPA/WPA2-Personal profiles with the derived PSK instead of the passphrase.
+    store_derived_psk: bool,
+    /// Ratings of passphrases we saved profiles with, by SSID. Copied to the known network once it connects.
+    passphrase_strengths: HashMap<String, PassphraseStrength>,
     base: Base<Object>
 }
 
+/// A connect call that hasn't completed or failed yet.
+struct ConnectionAttempt {
+    ssid: String,
+    /// True when the profile was saved by `generate_profile` just for this attempt.
+    created_profile: bool,
+    /// Started by `connect_with_password`, which undoes its profile change if the attempt fails.
+    transactional: bool,
+    /// Profile XML that was replaced, restored on rollback.
+    previous_profile: Option<String>,
+}
+
 #[derive(GodotClass)]
 #[class(init, base=Resource)]
 pub struct WiFiNetwork {
@@ -32,19 +92,234 @@ pub struct WiFiNetwork {
     #[var]
     network_security: NetworkSecurity,
     #[var]
+    encryption: EncryptionAlgorithm,
+    /// Secured with WEP. Only worth joining for legacy devices that can't do anything better.
+    #[var]
+    insecure: bool,
+    /// Worst severity from the security advisor, or "secure".
+    #[var]
+    security_rating: GString,
+    /// Each has code, severity, summary and recommendation keys.
+    #[var]
+    security_findings: Array<Dictionary>,
+    #[var]
     bars: u32,
 }
 
+#[derive(GodotClass)]
+#[class(init, base=Resource)]
+pub struct WiFiConnection {
+    #[var]
+    profile_name: GString,
+    #[var]
+    ssid: GString,
+    #[var]
+    bssid: GString,
+    #[var]
+    phy_type: GString,
+    #[var]
+    signal_quality: u32,
+    #[var]
+    rssi: i32,
+    #[var]
+    rx_rate_kbps: u32,
+    #[var]
+    tx_rate_kbps: u32,
+    #[var]
+    channel: u32,
+    #[var]
+    frequency_mhz: u32,
+    #[var]
+    security_enabled: bool,
+    #[var]
+    auth_algorithm: GString,
+    #[var]
+    cipher_algorithm: GString,
+    #[var]
+    one_x_enabled: bool,
+    #[var]
+    connection_mode: GString,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Resource)]
+pub struct WiFiStatistics {
+    #[var]
+    frames_sent: i64,
+    #[var]
+    frames_received: i64,
+    #[var]
+    multicast_frames_sent: i64,
+    #[var]
+    multicast_frames_received: i64,
+    #[var]
+    retries: i64,
+    #[var]
+    multiple_retries: i64,
+    #[var]
+    failed_transmissions: i64,
+    #[var]
+    ack_failures: i64,
+    #[var]
+    fcs_errors: i64,
+    #[var]
+    decryption_errors: i64,
+    #[var]
+    four_way_handshake_failures: i64,
+    #[var]
+    rx_bytes: i64,
+    #[var]
+    tx_bytes: i64,
+    #[var]
+    has_rates: bool,
+    #[var]
+    interval_secs: f64,
+    #[var]
+    frames_sent_per_sec: f64,
+    #[var]
+    frames_received_per_sec: f64,
+    #[var]
+    rx_bytes_per_sec: f64,
+    #[var]
+    tx_bytes_per_sec: f64,
+    #[var]
+    retry_rate: f64,
+    #[var]
+    failure_rate: f64,
+    #[var]
+    decryption_errors_per_sec: f64,
+}
+
+impl WiFiStatistics {
+    fn from_sample(statistics: &InterfaceStatistics, rates: Option<&StatisticsRates>) -> Gd<Self> {
+        let mut wifi_statistics = WiFiStatistics::new_gd();
+        let mut statistics_bind = wifi_statistics.bind_mut();
+
+        statistics_bind.frames_sent = statistics.frames_sent as i64;
+        statistics_bind.frames_received = statistics.frames_received as i64;
+        statistics_bind.multicast_frames_sent = statistics.multicast_frames_sent as i64;
+        statistics_bind.multicast_frames_received = statistics.multicast_frames_received as i64;
+        statistics_bind.retries = statistics.retries as i64;
+        statistics_bind.multiple_retries = statistics.multiple_retries as i64;
+        statistics_bind.failed_transmissions = statistics.failed_transmissions as i64;
+        statistics_bind.ack_failures = statistics.ack_failures as i64;
+        statistics_bind.fcs_errors = statistics.fcs_errors as i64;
+        statistics_bind.decryption_errors = statistics.decryption_errors as i64;
+        statistics_bind.four_way_handshake_failures = statistics.four_way_handshake_failures as i64;
+        statistics_bind.rx_bytes = statistics.rx_bytes as i64;
+        statistics_bind.tx_bytes = statistics.tx_bytes as i64;
+
+        if let Some(rates) = rates {
+            statistics_bind.has_rates = true;
+            statistics_bind.interval_secs = rates.interval_secs;
+            statistics_bind.frames_sent_per_sec = rates.frames_sent_per_sec;
+            statistics_bind.frames_received_per_sec = rates.frames_received_per_sec;
+            statistics_bind.rx_bytes_per_sec = rates.rx_bytes_per_sec;
+            statistics_bind.tx_bytes_per_sec = rates.tx_bytes_per_sec;
+            statistics_bind.retry_rate = rates.retry_rate;
+            statistics_bind.failure_rate = rates.failure_rate;
+            statistics_bind.decryption_errors_per_sec = rates.decryption_errors_per_sec;
+        }
+
+        drop(statistics_bind);
+        wifi_statistics
+    }
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Resource)]
+pub struct WiFiInterface {
+    #[var]
+    guid: GString,
+    #[var]
+    description: GString,
+    #[var]
+    state: WlanInterfaceState,
+    #[var]
+    active: bool,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Resource)]
+pub struct WiFiDiagnostics {
+    #[var]
+    ipv4_addresses: PackedStringArray,
+    #[var]
+    ipv6_addresses: PackedStringArray,
+    #[var]
+    gateways: PackedStringArray,
+    #[var]
+    dns_servers: PackedStringArray,
+    #[var]
+    gateway_reachable: bool,
+    #[var]
+    gateway_latency_ms: i64,
+    #[var]
+    dns_resolved: bool,
+    #[var]
+    dns_latency_ms: i64,
+    #[var]
+    tcp_connected: bool,
+    #[var]
+    tcp_latency_ms: i64,
+}
+
+/// Turns user:// and res:// paths into filesystem paths. Other paths are used as they are.
+fn resolve_store_path(path: &str) -> PathBuf {
+    if path.starts_with("user://") || path.starts_with("res://") {
+        PathBuf::from(ProjectSettings::singleton().globalize_path(path).to_string())
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+fn load_known_networks() -> KnownNetworkStore {
+    let path = resolve_store_path(DEFAULT_STORE_PATH);
+
+    match KnownNetworkStore::load(path.clone()) {
+        Ok(store) => store,
+        Err(error) => {
+            godot_error!("[SYSTEM] Failed To Load Known Networks. Starting Empty: {}", error);
+            KnownNetworkStore::empty(path)
+        },
+    }
+}
+
 #[godot_api]
 impl IObject for WlanAPI {
     fn init(base: Base<Object>) -> Self {
         godot_print!("[WLAN] Initializing NetworkManager");
 
+        let network_manager = NetworkManager::new(HANDLE(null_mut()), 2);
+        let notification_subscription = network_manager.subscribe(DEFAULT_QUEUE_CAPACITY, OverflowPolicy::DropOldest);
+
         Self {
-            network_manager: NetworkManager::new(HANDLE(null_mut()), 2),
+            network_manager,
+            state: WlanApiState::default(),
             notif_state: NotificationState::default(),
-            interface_state: WlanInterfaceState::default(),
-            known_networks: Array::new(),
+            notification_subscription,
+            known_networks: load_known_networks(),
+            statistics_interval: None,
+            last_statistics_poll: None,
+            pending_diagnostics: None,
+            event_pump: None,
+            event_pump_interval: 0.0,
+            recorder: None,
+            player: None,
+            selector: NetworkSelector::default(),
+            selection_weights: WeightedPolicy::default(),
+            auto_select_enabled: false,
+            pending_selection: None,
+            user_disconnected: HashSet::new(),
+            roaming: RoamingAssistant::default(),
+            reconnect: AutoReconnect::default(),
+            connection_attempt: None,
+            created_profile: None,
+            cancelled_attempt: None,
+            cancelled_network: None,
+            wep_key_index: 0,
+            store_derived_psk: true,
+            passphrase_strengths: HashMap::new(),
             base
         }
     }
@@ -58,191 +333,2025 @@ impl WlanAPI {
     #[signal]
     fn windows_profiles_found(ssid: GString);
 
-    // Connection Status Signals
-    #[signal]
-    fn connection_start();
+    // Connection Status Signals
+    #[signal]
+    fn connection_start();
+
+    #[signal]
+    fn connection_complete();
+
+    #[signal]
+    fn connection_attempt_fail();
+
+    #[signal]
+    fn invalid_password();
+
+    #[signal]
+    fn disconnected();
+
+    #[signal]
+    fn associating(ssid: GString);
+
+    #[signal]
+    fn associated(ssid: GString, bssid: GString);
+
+    #[signal]
+    fn authenticating(ssid: GString);
+
+    #[signal]
+    fn roaming_start(bssid: GString);
+
+    #[signal]
+    fn roaming_end(bssid: GString);
+
+    #[signal]
+    fn signal_quality_changed(quality: u32);
+
+    // General Notification Signals
+    #[signal]
+    fn notification_received(name: GString, data: Dictionary);
+
+    #[signal]
+    fn autoconf_changed(enabled: bool);
+
+    #[signal]
+    fn profile_changed();
+
+    #[signal]
+    fn profile_name_changed(old_name: GString, new_name: GString);
+
+    #[signal]
+    fn network_available();
+
+    #[signal]
+    fn network_not_available();
+
+    #[signal]
+    fn scan_complete();
+
+    #[signal]
+    fn scan_failed(reason_code: i64);
+
+    #[signal]
+    fn adapter_operation_mode_changed(mode: i64);
+
+    // Interface Hot-Plug Signals
+    #[signal]
+    fn interface_added(guid: GString);
+
+    #[signal]
+    fn interface_removed(guid: GString);
+
+    #[signal]
+    fn active_interface_changed(guid: GString);
+
+    #[signal]
+    fn replay_finished();
+
+    #[signal]
+    fn state_changed(state: WlanApiState);
+
+    // WLAN Service Signals
+    #[signal]
+    fn service_lost();
+
+    #[signal]
+    fn service_restored();
+
+    #[signal]
+    fn radio_state_changed(software_on: bool, hardware_on: bool);
+
+    #[signal]
+    fn statistics_updated(statistics: Gd<WiFiStatistics>);
+
+    #[signal]
+    fn diagnostics_completed(diagnostics: Gd<WiFiDiagnostics>);
+
+    #[signal]
+    fn network_selected(ssid: GString, score: f64);
+
+    // Roaming Assistant Signals
+    #[signal]
+    fn roam_started(old_bssid: GString, target_bssid: GString);
+
+    #[signal]
+    fn roam_completed(old_bssid: GString, new_bssid: GString);
+
+    #[signal]
+    fn roam_failed(target_bssid: GString);
+
+    // Auto-Reconnect Signals
+    #[signal]
+    fn reconnecting(attempt: u32);
+
+    #[signal]
+    fn reconnect_failed();
+
+    #[signal]
+    fn connection_cancelled(ssid: GString);
+
+    #[cfg(debug_assertions)]
+    #[func]
+    fn test_xml_data(ssid: GString) {
+        globals::save_xml_to_disk(ssid.to_string().as_str());
+    }
+
+    /// Returns true when the manager came up Ready. Check `get_state` for why it didn't.
+    #[func]
+    fn initialize_network_manager(&mut self) -> bool {
+        if self.state == WlanApiState::ShuttingDown {
+            godot_error!("[WLAN] Cannot Initialize While Shutting Down");
+            return false;
+        }
+
+        self.network_manager.init();
+        self.set_state(self.session_state());
+        self.attach_event_pump();
+
+        // Selection runs once the scan completes
+        if self.auto_select_enabled && self.state == WlanApiState::Ready {
+            if let Some(guid) = self.network_manager.get_active_interface_guid() {
+                self.network_manager.request_scan(&guid);
+            }
+        }
+
+        self.state == WlanApiState::Ready
+    }
+
+    #[func]
+    fn get_state(&self) -> WlanApiState {
+        self.state
+    }
+
+    #[func]
+    fn is_ready(&self) -> bool {
+        self.state == WlanApiState::Ready
+    }
+
+    #[func]
+    fn get_event_pump(&self) -> Option<Gd<WlanEventPump>> {
+        self.event_pump.clone()
+    }
+
+    /// Sets how often the event pump drains notifications, in seconds. Zero drains every frame.
+    #[func]
+    fn set_event_pump_interval(&mut self, seconds: f64) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.event_pump_interval = seconds.max(0.0);
+
+        if let Some(pump) = self.event_pump.as_mut() {
+            pump.bind_mut().poll_interval = self.event_pump_interval;
+        }
+        true
+    }
+
+    #[func]
+    fn fetch_network_data(&mut self) -> bool {
+        if !self.require_ready() {
+            return false;
+        }
+
+        if self.network_manager.open_handle().is_err() {
+            return false;
+        }
+        godot_print!("[WLAN] NetworkManager Ready");
+        godot_print!("[WLAN] Scanning for Networks");
+
+        let Some(guid) = self.active_guid() else { return false };
+        self.refresh_networks_for(&guid);
+        self.signals().network_data_fetched().emit();
+        true
+    }
+
+    #[func]
+    fn get_interfaces(&mut self) -> Array<Gd<WiFiInterface>> {
+        // Listing interfaces is how scripts find out an adapter came back, so NoAdapter is allowed too
+        if !matches!(self.state, WlanApiState::Ready | WlanApiState::NoAdapter) {
+            godot_error!("[WLAN] Cannot List Interfaces While {:?}", self.state);
+            return Array::new();
+        }
+
+        self.network_manager.refresh_interfaces();
+        self.update_state();
+        let active_guid = self.network_manager.get_active_interface_guid();
+
+        let mut interfaces = Array::new();
+        for ifo in self.network_manager.get_interface_list() {
+            let state = match self.network_manager.get_interface_data(&ifo.InterfaceGuid) {
+                Some(data) => data.connection_state,
+                None => WlanInterfaceState::default(),
+            };
+
+            let mut wifi_interface = WiFiInterface::new_gd();
+            let mut interface_bind = wifi_interface.bind_mut();
+
+            interface_bind.guid = GString::from(convert_guid_to_string(&ifo.InterfaceGuid));
+            interface_bind.description = GString::from(convert_u16_slice_to_string(&ifo.strInterfaceDescription));
+            interface_bind.state = state;
+            interface_bind.active = active_guid == Some(ifo.InterfaceGuid);
+
+            drop(interface_bind);
+            interfaces.push(&wifi_interface);
+        }
+
+        interfaces
+    }
+
+    #[func]
+    fn get_active_interface(&self) -> GString {
+        if !self.require_configurable() {
+            return GString::new();
+        }
+
+        match self.network_manager.get_active_interface_guid() {
+            Some(guid) => GString::from(convert_guid_to_string(&guid)),
+            None => GString::new(),
+        }
+    }
+
+    /// Works in NoAdapter too, so a script can pick an adapter that just arrived.
+    #[func]
+    fn set_active_interface(&mut self, guid: GString) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        let Some(guid) = convert_string_to_guid(&guid.to_string()) else {
+            godot_error!("[WLAN] Invalid Interface GUID: {}", guid);
+            return false;
+        };
+
+        if self.network_manager.find_interface(&guid).is_none() {
+            self.network_manager.refresh_interfaces();
+        }
+
+        if !self.network_manager.set_active_interface(&guid) {
+            return false;
+        }
+
+        self.update_state();
+        true
+    }
+
+    #[func]
+    fn is_radio_on(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        match self.network_manager.get_radio_state(&guid) {
+            Some(state) => state.software_on && state.hardware_on,
+            None => false,
+        }
+    }
+
+    #[func]
+    fn is_hardware_radio_on(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        match self.network_manager.get_radio_state(&guid) {
+            Some(state) => state.hardware_on,
+            None => false,
+        }
+    }
+
+    #[func]
+    fn is_software_radio_on(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        match self.network_manager.get_radio_state(&guid) {
+            Some(state) => state.software_on,
+            None => false,
+        }
+    }
+
+    #[func]
+    fn set_radio_enabled(&mut self, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.set_radio_state(&guid, enabled)
+    }
+
+    #[func]
+    fn is_autoconf_enabled(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.query_opcode::<AutoconfEnabled>(&guid).unwrap_or(false)
+    }
+
+    #[func]
+    fn set_autoconf_enabled(&self, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.set_opcode::<AutoconfEnabled>(&guid, enabled)
+    }
+
+    #[func]
+    fn is_background_scan_enabled(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.query_opcode::<BackgroundScanEnabled>(&guid).unwrap_or(false)
+    }
+
+    #[func]
+    fn set_background_scan_enabled(&self, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.set_opcode::<BackgroundScanEnabled>(&guid, enabled)
+    }
+
+    #[func]
+    fn is_media_streaming_mode(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.query_opcode::<MediaStreamingMode>(&guid).unwrap_or(false)
+    }
+
+    #[func]
+    fn set_media_streaming_mode(&self, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.set_opcode::<MediaStreamingMode>(&guid, enabled)
+    }
+
+    #[func]
+    fn is_safe_mode_supported(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.query_opcode::<SupportedSafeMode>(&guid).unwrap_or(false)
+    }
+
+    #[func]
+    fn is_safe_mode_certified(&self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.query_opcode::<CertifiedSafeMode>(&guid).unwrap_or(false)
+    }
+
+    /// "infrastructure", "independent" (ad hoc) or "any". Empty if the query failed.
+    #[func]
+    fn get_bss_type(&self) -> GString {
+        let Some(guid) = self.active_guid() else { return GString::new() };
+
+        let bss_type = match self.network_manager.query_opcode::<BssType>(&guid) {
+            Some(bss_type) if bss_type == dot11_BSS_type_infrastructure => "infrastructure",
+            Some(bss_type) if bss_type == dot11_BSS_type_independent => "independent",
+            Some(_) => "any",
+            None => "",
+        };
+
+        GString::from(bss_type)
+    }
+
+    /// Takes the same values `get_bss_type` returns.
+    #[func]
+    fn set_bss_type(&self, bss_type: GString) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+
+        let value = match bss_type.to_string().as_str() {
+            "infrastructure" => dot11_BSS_type_infrastructure,
+            "independent" => dot11_BSS_type_independent,
+            "any" => dot11_BSS_type_any,
+            _ => {
+                godot_error!("[WLAN] Unknown BSS Type: {}", bss_type);
+                return false;
+            },
+        };
+
+        self.network_manager.set_opcode::<BssType>(&guid, value)
+    }
+
+    /// Queries the active interface's state directly rather than relying on the last notification.
+    #[func]
+    fn get_interface_state(&mut self) -> WlanInterfaceState {
+        let Some(guid) = self.active_guid() else { return WlanInterfaceState::default() };
+        self.network_manager.refresh_interface_state(&guid).unwrap_or_default()
+    }
+
+    #[func]
+    fn get_supported_countries(&self) -> PackedStringArray {
+        let Some(guid) = self.active_guid() else { return PackedStringArray::new() };
+
+        self.network_manager.query_opcode::<SupportedCountryOrRegionStrings>(&guid)
+            .unwrap_or_default()
+            .iter()
+            .map(|code| GString::from(code.as_str()))
+            .collect()
+    }
+
+    /// Each entry has "auth" and "cipher" keys. `adhoc` selects the ad hoc list instead of infrastructure.
+    #[func]
+    fn get_supported_auth_cipher_pairs(&self, adhoc: bool) -> Array<Dictionary> {
+        let Some(guid) = self.active_guid() else { return Array::new() };
+
+        let pairs = if adhoc {
+            self.network_manager.query_opcode::<SupportedAdhocAuthCipherPairs>(&guid)
+        } else {
+            self.network_manager.query_opcode::<SupportedInfrastructureAuthCipherPairs>(&guid)
+        };
+
+        let mut array = Array::new();
+        for pair in pairs.unwrap_or_default() {
+            let mut dictionary = Dictionary::new();
+            dictionary.set("auth", convert_auth_algorithm_to_string(pair.AuthAlgoId).as_str());
+            dictionary.set("cipher", convert_cipher_algorithm_to_string(pair.CipherAlgoId).as_str());
+            array.push(&dictionary);
+        }
+
+        array
+    }
+
+    #[func]
+    fn get_statistics(&mut self) -> Option<Gd<WiFiStatistics>> {
+        let guid = self.active_guid()?;
+        let (statistics, rates) = self.network_manager.sample_statistics(&guid)?;
+
+        Some(WiFiStatistics::from_sample(&statistics, rates.as_ref()))
+    }
+
+    /// Sets how often statistics are sampled while polling. Zero or less disables sampling.
+    #[func]
+    fn set_statistics_interval(&mut self, seconds: f64) {
+        self.statistics_interval = if seconds > 0.0 {
+            Some(Duration::from_secs_f64(seconds))
+        } else {
+            None
+        };
+        self.last_statistics_poll = None;
+    }
+
+    fn poll_statistics(&mut self) {
+        let Some(interval) = self.statistics_interval else { return };
+        if let Some(last_poll) = self.last_statistics_poll {
+            if last_poll.elapsed() < interval {
+                return;
+            }
+        }
+
+        self.last_statistics_poll = Some(Instant::now());
+
+        let Some(guid) = self.network_manager.get_active_interface_guid() else { return };
+        if let Some((statistics, rates)) = self.network_manager.sample_statistics(&guid) {
+            let wifi_statistics = WiFiStatistics::from_sample(&statistics, rates.as_ref());
+            self.signals().statistics_updated().emit(&wifi_statistics);
+        }
+    }
+
+    fn set_state(&mut self, state: WlanApiState) {
+        if self.state == state {
+            return;
+        }
+
+        godot_print!("[WLAN] State Changed: {:?} -> {:?}", self.state, state);
+        self.state = state;
+        self.signals().state_changed().emit(state);
+    }
+
+    /// State implied by the handle and interfaces. Uninitialized and ShuttingDown are only left explicitly.
+    fn session_state(&self) -> WlanApiState {
+        if !self.network_manager.is_handle_open || self.network_manager.is_service_lost() {
+            WlanApiState::ServiceDown
+        } else if self.network_manager.get_active_interface_guid().is_none() {
+            WlanApiState::NoAdapter
+        } else {
+            WlanApiState::Ready
+        }
+    }
+
+    fn update_state(&mut self) {
+        if matches!(self.state, WlanApiState::Uninitialized | WlanApiState::ShuttingDown) {
+            return;
+        }
+
+        self.set_state(self.session_state());
+    }
+
+    fn require_ready(&self) -> bool {
+        let reason = match self.state {
+            WlanApiState::Ready => return true,
+            WlanApiState::Uninitialized => "Call initialize_network_manager First",
+            WlanApiState::NoAdapter => "No Wireless Adapter Is Available",
+            WlanApiState::ServiceDown => "The WLAN Service Is Unavailable",
+            WlanApiState::ShuttingDown => "WlanAPI Is Shutting Down",
+        };
+
+        godot_error!("[WLAN] WlanAPI Is Not Ready: {}", reason);
+        false
+    }
+
+    /// Settings and known networks can change in any state except shutdown.
+    fn require_configurable(&self) -> bool {
+        if self.state == WlanApiState::ShuttingDown {
+            godot_error!("[WLAN] WlanAPI Is Shutting Down");
+            return false;
+        }
+
+        true
+    }
+
+    fn active_guid(&self) -> Option<GUID> {
+        if !self.require_ready() {
+            return None;
+        }
+
+        let guid = self.network_manager.get_active_interface_guid();
+        if guid.is_none() {
+            godot_error!("[WLAN] No Active Interface. Call initialize_network_manager First.");
+        }
+
+        guid
+    }
+
+    fn target_guid(&self, guid: &GString) -> Option<GUID> {
+        if !self.require_ready() {
+            return None;
+        }
+
+        let guid = convert_string_to_guid(&guid.to_string())?;
+        if self.network_manager.find_interface(&guid).is_none() {
+            godot_error!("[WLAN] Unknown Interface: {:?}", guid);
+            return None;
+        }
+
+        Some(guid)
+    }
+
+    /// Handles a single pending notification and returns whether there was one. Prefer the event pump,
+    /// which drains the whole queue.
+    #[func]
+    fn poll_connection_status(&mut self) -> bool {
+        if self.state == WlanApiState::ShuttingDown {
+            return false;
+        }
+
+        self.check_session();
+        self.poll_statistics();
+        self.poll_diagnostics();
+        self.poll_roaming();
+        self.poll_reconnect();
+
+        self.warn_on_dropped_notifications();
+
+        if self.player.is_some() {
+            self.discard_live_notifications();
+            return self.replay_due_events() > 0;
+        }
+
+        match self.notification_subscription.try_recv() {
+            Some(event) => {
+                self.dispatch_notification(event);
+                true
+            },
+            None => {
+                if let NotificationState::Empty = self.notif_state {
+                    godot_warn!("[WLAN] Receiver Was Empty");
+                    self.notif_state = NotificationState::StateKnown;
+                }
+                false
+            },
+        }
+    }
+
+    /// Handles every pending notification in the order they arrived and returns how many there were.
+    #[func]
+    pub fn process_pending_notifications(&mut self) -> i64 {
+        if self.state == WlanApiState::ShuttingDown {
+            return 0;
+        }
+
+        self.check_session();
+        self.poll_statistics();
+        self.poll_diagnostics();
+        self.poll_roaming();
+        self.poll_reconnect();
+
+        self.warn_on_dropped_notifications();
+
+        if self.player.is_some() {
+            self.discard_live_notifications();
+            return self.replay_due_events();
+        }
+
+        let pending = self.notification_subscription.drain();
+        let count = pending.len() as i64;
+
+        for event in pending {
+            self.dispatch_notification(event);
+        }
+
+        count
+    }
+
+    /// Starts writing notifications, scan results and connect calls to a JSON lines trace.
+    #[func]
+    fn start_recording(&mut self, path: GString) -> bool {
+        if !self.require_ready() {
+            return false;
+        }
+
+        match TraceRecorder::create(Path::new(&path.to_string())) {
+            Ok(recorder) => {
+                godot_print!("[WLAN] Recording Trace To {}", path);
+                self.recorder = Some(recorder);
+                true
+            },
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Create Trace File {}: {}", path, error);
+                false
+            },
+        }
+    }
+
+    #[func]
+    fn stop_recording(&mut self) {
+        if self.recorder.take().is_some() {
+            godot_print!("[WLAN] Trace Recording Stopped");
+        }
+    }
+
+    #[func]
+    fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Replays a recorded trace through the normal notification handling. `speed` of 1.0 is
+    /// real time, 0.0 replays everything on the next poll. Live notifications are ignored until it ends.
+    #[func]
+    fn start_replay(&mut self, path: GString, speed: f64) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        match TracePlayer::load(Path::new(&path.to_string()), speed) {
+            Ok(player) => {
+                godot_print!("[WLAN] Replaying Trace {} At {}x", path, speed);
+                self.player = Some(player);
+                true
+            },
+            Err(error) => {
+                godot_error!("[WLAN] Failed To Load Trace {}: {}", path, error);
+                false
+            },
+        }
+    }
+
+    #[func]
+    fn stop_replay(&mut self) {
+        if self.player.take().is_some() {
+            godot_print!("[WLAN] Trace Replay Stopped");
+        }
+    }
+
+    #[func]
+    fn is_replaying(&self) -> bool {
+        self.player.is_some()
+    }
+
+    fn dispatch_notification(&mut self, event: InterfaceNotification) {
+        if self.recorder.is_some() {
+            self.record_at(TraceEvent::from_notification(&event), event.published_at);
+        }
+
+        self.handle_notification(event.guid, event.notification);
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        self.record_at(event, Instant::now());
+    }
+
+    fn record_at(&mut self, event: TraceEvent, at: Instant) {
+        let Some(recorder) = self.recorder.as_mut() else { return };
+
+        if let Err(error) = recorder.record(event, at) {
+            godot_error!("[WLAN] Failed To Write Trace. Recording Stopped: {}", error);
+            self.recorder = None;
+        }
+    }
+
+    fn record_connect(&mut self, guid: &GUID, ssid: &GString) {
+        self.record(TraceEvent::Connect {
+            interface: convert_guid_to_string(guid),
+            ssid: ssid.to_string(),
+        });
+    }
+
+    fn refresh_networks_for(&mut self, guid: &GUID) {
+        // While replaying, the scan results come from the trace instead
+        if self.player.is_some() {
+            return;
+        }
+
+        self.network_manager.refresh_networks(guid);
+        self.observe_signals(guid);
+
+        if self.recorder.is_some() {
+            let networks = self.network_manager.get_networks(guid)
+                .values()
+                .map(TraceNetwork::from_network)
+                .collect();
+            let bss_entries = self.network_manager.get_interface_data(guid)
+                .map_or_else(Vec::new, |data| data.bss_entries.clone());
+
+            self.record(TraceEvent::ScanResult { interface: convert_guid_to_string(guid), networks, bss_entries });
+        }
+
+        if self.network_manager.get_active_interface_guid() != Some(*guid) {
+            return;
+        }
+
+        if self.roaming.is_awaiting_scan() {
+            self.try_roam(guid);
+        }
+
+        if self.auto_select_enabled {
+            self.auto_select_network(guid);
+        }
+    }
+
+    fn discard_live_notifications(&self) {
+        let discarded = self.notification_subscription.drain().len();
+        if discarded > 0 {
+            godot_print!("[WLAN] Ignored {} Live Notifications During Replay", discarded);
+        }
+    }
+
+    fn replay_due_events(&mut self) -> i64 {
+        let mut count = 0;
+
+        loop {
+            let Some(player) = self.player.as_mut() else { break };
+
+            let Some(event) = player.next_due() else {
+                if player.is_finished() {
+                    self.player = None;
+                    godot_print!("[WLAN] Trace Replay Finished");
+                    self.signals().replay_finished().emit();
+                }
+                break;
+            };
+
+            self.replay_event(event);
+            count += 1;
+        }
+
+        count
+    }
+
+    fn replay_event(&mut self, event: TraceEvent) {
+        match event {
+            TraceEvent::Notification { interface, raw, .. } => {
+                let guid = self.replay_guid(&interface);
+                match raw.decode() {
+                    Some(notification) => self.handle_notification(guid, notification),
+                    None => godot_warn!("[WLAN] Trace Notification Has Unknown Source {}", raw.source),
+                }
+            },
+            TraceEvent::ScanResult { interface, networks, bss_entries } => {
+                let guid = self.replay_guid(&interface);
+                let interface_data = self.network_manager.get_interface_data_mut(&guid);
+                interface_data.networks = networks.into_iter()
+                    .map(TraceNetwork::into_network)
+                    .map(|network| (network.get_ssid(), network))
+                    .collect();
+                interface_data.bss_entries = bss_entries;
+                self.observe_signals(&guid);
+
+                self.signals().network_data_fetched().emit();
+            },
+            TraceEvent::Connect { ssid, .. } => godot_print!("[WLAN] Trace: Connect Requested To {}", ssid),
+            TraceEvent::Disconnect { .. } => godot_print!("[WLAN] Trace: Disconnect Requested"),
+        }
+    }
+
+    fn replay_guid(&self, interface: &str) -> GUID {
+        // Traces usually come from another machine, so unknown interfaces map onto the active one
+        match convert_string_to_guid(interface) {
+            Some(guid) if self.network_manager.find_interface(&guid).is_some() => guid,
+            parsed => self.network_manager.get_active_interface_guid()
+                .or(parsed)
+                .unwrap_or(GUID::zeroed()),
+        }
+    }
+
+    fn check_session(&mut self) {
+        match self.network_manager.check_session() {
+            Some(SessionEvent::Lost) => {
+                self.update_state();
+                self.signals().service_lost().emit();
+            },
+            Some(SessionEvent::Restored { interface_changed }) => {
+                self.update_state();
+                self.signals().service_restored().emit();
+
+                if interface_changed {
+                    if let Some(guid) = self.network_manager.get_active_interface_guid() {
+                        self.signals().active_interface_changed().emit(GString::from(convert_guid_to_string(&guid)));
+                    }
+                }
+            },
+            None => {},
+        }
+    }
+
+    fn warn_on_dropped_notifications(&self) {
+        let dropped = self.notification_subscription.take_dropped_count();
+        if dropped > 0 {
+            godot_warn!("[WLAN] Notification Queue Overflowed. {} Notifications Dropped.", dropped);
+        }
+    }
+
+    fn attach_event_pump(&mut self) {
+        if let Some(pump) = &self.event_pump {
+            if pump.is_instance_valid() {
+                return;
+            }
+        }
+
+        let Some(main_loop) = Engine::singleton().get_main_loop() else {
+            godot_warn!("[WLAN] No Main Loop. Event Pump Not Added.");
+            return;
+        };
+
+        let Ok(scene_tree) = main_loop.try_cast::<SceneTree>() else {
+            godot_warn!("[WLAN] Main Loop Is Not A SceneTree. Event Pump Not Added.");
+            return;
+        };
+
+        let Some(mut root) = scene_tree.get_root() else {
+            godot_warn!("[WLAN] SceneTree Has No Root. Event Pump Not Added.");
+            return;
+        };
+
+        let mut pump = WlanEventPump::new_alloc();
+        pump.set_name("WlanEventPump");
+        pump.bind_mut().poll_interval = self.event_pump_interval;
+
+        // The tree may be mid-update when this is called from a script, so defer the insertion
+        root.call_deferred("add_child", &[pump.to_variant()]);
+        godot_print!("[WLAN] Event Pump Added To Scene Tree");
+
+        self.event_pump = Some(pump);
+    }
+
+    fn handle_notification(&mut self, guid: GUID, notification: ConnectionNotifcation) {
+        let guid_string = GString::from(convert_guid_to_string(&guid));
+        let is_active = self.network_manager.get_active_interface_guid() == Some(guid);
+
+        match &notification {
+            ConnectionNotifcation::InterfaceArrival => {
+                let previous_active = self.network_manager.get_active_interface_guid();
+                self.network_manager.handle_interface_arrival(&guid);
+                self.signals().interface_added().emit(guid_string.clone());
+                self.update_state();
+
+                if let Some(new_guid) = self.network_manager.get_active_interface_guid() {
+                    if previous_active != Some(new_guid) {
+                        self.signals().active_interface_changed().emit(GString::from(convert_guid_to_string(&new_guid)));
+                    }
+                }
+            },
+            ConnectionNotifcation::InterfaceRemoval => {
+                let failover = self.network_manager.handle_interface_removal(&guid);
+                self.user_disconnected.remove(&guid);
+                self.signals().interface_removed().emit(guid_string.clone());
+                self.update_state();
+
+                if let Some(new_guid) = failover {
+                    self.signals().active_interface_changed().emit(GString::from(convert_guid_to_string(&new_guid)));
+                }
+            },
+            ConnectionNotifcation::RadioStateChange(_) => {
+                if let Some(radio_state) = self.network_manager.refresh_radio_state(&guid) {
+                    godot_print!("[WLAN] Radio State Changed: {:?}", radio_state);
+                    if is_active {
+                        self.signals().radio_state_changed().emit(radio_state.software_on, radio_state.hardware_on);
+                    }
+                }
+            },
+            _ => {}
+        }
+
+        let interface_data = self.network_manager.get_interface_data_mut(&guid);
+        match &notification {
+            ConnectionNotifcation::ConnectionStart(data) => {
+                interface_data.connection_state = WlanInterfaceState::Associating;
+                interface_data.connecting_ssid = Some(data.ssid.clone());
+            },
+            ConnectionNotifcation::Associating(data) => {
+                interface_data.connection_state = WlanInterfaceState::Associating;
+                interface_data.connecting_ssid = Some(data.ssid.clone());
+            },
+            ConnectionNotifcation::Authenticating(_) => interface_data.connection_state = WlanInterfaceState::Authenticating,
+            ConnectionNotifcation::ConnectionComplete(_) => {
+                interface_data.connection_state = WlanInterfaceState::Connected;
+                interface_data.connecting_ssid = None;
+            },
+            ConnectionNotifcation::Disconnecting(_) => interface_data.connection_state = WlanInterfaceState::Disconnecting,
+            ConnectionNotifcation::Disconnected(_) | ConnectionNotifcation::ConnectionAttemptFail(_) => {
+                interface_data.connection_state = WlanInterfaceState::Disconnected;
+                interface_data.connecting_ssid = None;
+            },
+            ConnectionNotifcation::Error => interface_data.connection_state = WlanInterfaceState::Unavailable,
+            _ => {}
+        }
+
+        // Windows or another app reconnected it, so a later drop is unexpected again
+        if let ConnectionNotifcation::ConnectionComplete(_) = &notification {
+            self.user_disconnected.remove(&guid);
+            self.cancelled_network = None;
+        }
+
+        if !is_active {
+            return;
+        }
+
+        godot_print!("[WLAN] Got Data From Notification Receiver: {:?}", notification);
+        self.signals().notification_received().emit(GString::from(notification.get_name()), notification.to_dictionary());
+
+        // A replayed trace comes from someone else's machine, it must not touch the real store or profiles
+        let replaying = self.player.is_some();
+
+        match &notification {
+            ConnectionNotifcation::ConnectionStart(_) => self.signals().connection_start().emit(),
+            ConnectionNotifcation::ConnectionComplete(data) => {
+                self.pending_selection = None;
+                // A completion for another network, like an auto-connect, leaves our attempt running
+                if let Some(attempt) = self.connection_attempt.take_if(|attempt| attempt.ssid == data.ssid) {
+                    if attempt.transactional && !replaying {
+                        godot_print!("[WLAN] Connected With New Profile For {}", data.ssid);
+                        self.add_network_to_known_networks(GString::from(data.ssid.as_str()));
+                    }
+                }
+                if self.reconnect.is_active() {
+                    godot_print!("[WLAN] Reconnected To {}", data.ssid);
+                    self.reconnect.cancel();
+                }
+
+                if let Some(attempt) = self.roaming.finish_roam() {
+                    let new_bssid = self.network_manager.get_connection_info(&guid)
+                        .map_or(attempt.target.bssid, |info| info.bssid);
+
+                    godot_print!("[WLAN] Roamed From {} To {}", convert_bssid_to_string(&attempt.old_bssid), convert_bssid_to_string(&new_bssid));
+                    self.signals().roam_completed().emit(
+                        GString::from(convert_bssid_to_string(&attempt.old_bssid)),
+                        GString::from(convert_bssid_to_string(&new_bssid)));
+                }
+
+                if !replaying {
+                    if let Some(strength) = self.passphrase_strengths.get(&data.ssid).copied() {
+                        if let Some(known_network) = self.known_networks.get_mut(&data.ssid) {
+                            known_network.passphrase_strength = Some(strength);
+                        }
+                    }
+                    if self.known_networks.record_connection(&data.ssid) {
+                        self.save_known_networks();
+                    }
+                }
+                self.signals().connection_complete().emit();
+            },
+            ConnectionNotifcation::ConnectionAttemptFail(data) => {
+                self.pending_selection = None;
+                let attempt = self.connection_attempt.take_if(|attempt| attempt.ssid == data.ssid);
+
+                // Already reported through connection_cancelled
+                if self.cancelled_attempt.take().is_some_and(|ssid| ssid == data.ssid) {
+                    return;
+                }
+
+                if let Some(attempt) = attempt {
+                    if attempt.transactional && !replaying {
+                        self.roll_back_profile(&guid, attempt);
+                    }
+                }
+
+                if self.reconnect.is_active() {
+                    // Retrying with the wrong key only locks the account out faster
+                    if notification.is_invalid_password() {
+                        self.reconnect.give_up();
+                        godot_warn!("[WLAN] Reconnect To {} Stopped: Key Rejected", data.ssid);
+                        self.signals().reconnect_failed().emit();
+                    } else {
+                        self.reconnect.attempt_failed();
+                    }
+                }
+
+                if let Some(attempt) = self.roaming.finish_roam() {
+                    godot_warn!("[WLAN] Roam To {} Failed", convert_bssid_to_string(&attempt.target.bssid));
+                    self.signals().roam_failed().emit(GString::from(convert_bssid_to_string(&attempt.target.bssid)));
+                }
+
+                if !replaying && self.known_networks.record_failure(&data.ssid) {
+                    self.save_known_networks();
+                }
+
+                if notification.is_invalid_password() {
+                    self.signals().invalid_password().emit();
+                }
+                self.signals().connection_attempt_fail().emit();
+            },
+            ConnectionNotifcation::Disconnected(data) => {
+                self.signals().disconnected().emit();
+
+                // A roam disconnects from the old BSS first, that one is expected
+                if !self.roaming.is_roaming() {
+                    self.roaming.reset();
+                }
+
+                let switching = self.roaming.is_roaming() || self.pending_selection.is_some();
+                if !self.user_disconnected.contains(&guid) && !switching && !self.reconnect.is_active() && notification.is_unexpected_disconnect() {
+                    godot_warn!("[WLAN] Lost Connection To {} Unexpectedly. Reason Code: {}", data.ssid, data.reason_code);
+
+                    let target = ReconnectTarget { guid, profile_name: data.profile_name.clone(), ssid: data.ssid.clone() };
+                    self.reconnect.start(target);
+                }
+
+                // Falls through to selection only when auto-reconnect didn't take this one
+                if self.auto_select_enabled {
+                    self.auto_select_network(&guid);
+                }
+            },
+            ConnectionNotifcation::AutoconfEnabled => self.signals().autoconf_changed().emit(true),
+            ConnectionNotifcation::AutoconfDisabled => self.signals().autoconf_changed().emit(false),
+            ConnectionNotifcation::ProfileChange => self.signals().profile_changed().emit(),
+            ConnectionNotifcation::ProfileNameChange { old_name, new_name } => {
+                self.signals().profile_name_changed().emit(GString::from(old_name.as_str()), GString::from(new_name.as_str()));
+            },
+            ConnectionNotifcation::NetworkAvailable => self.signals().network_available().emit(),
+            ConnectionNotifcation::NetworkNotAvailable => self.signals().network_not_available().emit(),
+            ConnectionNotifcation::ScanComplete => {
+                self.signals().scan_complete().emit();
+
+                if self.auto_select_enabled || self.roaming.is_awaiting_scan() {
+                    self.refresh_networks_for(&guid);
+                }
+            },
+            ConnectionNotifcation::ScanFail(reason_code) => {
+                self.roaming.scan_failed();
+                self.signals().scan_failed().emit(*reason_code as i64);
+            },
+            ConnectionNotifcation::SignalQualityChange(quality) => {
+                self.roaming.observe_signal(*quality);
+                self.signals().signal_quality_changed().emit(*quality);
+            },
+            ConnectionNotifcation::Associating(data) => self.signals().associating().emit(GString::from(data.ssid.as_str())),
+            ConnectionNotifcation::Associated(data) => {
+                self.signals().associated().emit(GString::from(data.ssid.as_str()), GString::from(convert_bssid_to_string(&data.bssid)));
+            },
+            ConnectionNotifcation::Authenticating(data) => self.signals().authenticating().emit(GString::from(data.ssid.as_str())),
+            ConnectionNotifcation::RoamingStart(data) => self.signals().roaming_start().emit(GString::from(convert_bssid_to_string(&data.bssid))),
+            ConnectionNotifcation::RoamingEnd(data) => self.signals().roaming_end().emit(GString::from(convert_bssid_to_string(&data.bssid))),
+            ConnectionNotifcation::AdapterOperationModeChange(mode) => self.signals().adapter_operation_mode_changed().emit(*mode as i64),
+            _ => {}
+        }
+    }
+
+    /// Adds the network to the known networks store, using the security from the last scan if it is in it.
+    #[func]
+    fn add_network_to_known_networks(&mut self, ssid: GString) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        let ssid_string = ssid.to_string();
+        let scanned = self.network_manager.get_active_interface_guid()
+            .and_then(|guid| self.network_manager.get_network(&guid, &ssid_string));
+
+        // Keep the bytes the scan saw, a non UTF-8 SSID doesn't survive the round trip through a string
+        let (ssid_bytes, security) = match scanned {
+            Some(network) => (network.get_ssid_bytes().to_vec(), network.get_security()),
+            None => (ssid_string.into_bytes(), NetworkSecurity::Unknown),
+        };
+
+        if !self.known_networks.add(&ssid_bytes, security) {
+            return false;
+        }
+
+        self.save_known_networks()
+    }
+
+    #[func]
+    fn remove_known_network(&mut self, ssid: GString) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        if !self.known_networks.remove(&ssid.to_string()) {
+            godot_warn!("[SYSTEM] {} Is Not A Known Network", ssid);
+            return false;
+        }
+
+        self.save_known_networks()
+    }
+
+    /// Reloads the known networks store from disk.
+    #[func]
+    pub fn read_from_known_networks(&mut self) -> bool {
+        let path = self.known_networks.get_path().to_path_buf();
+
+        match KnownNetworkStore::load(path) {
+            Ok(store) => {
+                godot_print!("[SYSTEM] Loaded {} Known Network(s)", store.get_networks().len());
+                self.known_networks = store;
+                true
+            },
+            Err(error) => {
+                godot_error!("[SYSTEM] Failed To Load Known Networks: {}", error);
+                false
+            },
+        }
+    }
+
+    /// Each entry has ssid, security, profile_name, auto_connect, priority, first_connected,
+    /// last_connected and connect_count keys. Times are Unix seconds, or -1 if never connected.
+    #[func]
+    fn get_known_networks(&self) -> Array<Dictionary> {
+        let mut networks = self.known_networks.get_networks().to_vec();
+        networks.sort_by_key(|network| network.priority);
+
+        let mut array = Array::new();
+        for network in &networks {
+            array.push(&network.to_dictionary());
+        }
+
+        array
+    }
+
+    /// Moves the store to `path`, which may be a user:// path, and loads whatever is there.
+    #[func]
+    fn set_known_networks_path(&mut self, path: GString) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        let store_path = resolve_store_path(&path.to_string());
+
+        match KnownNetworkStore::load(store_path) {
+            Ok(store) => {
+                self.known_networks = store;
+                true
+            },
+            Err(error) => {
+                godot_error!("[SYSTEM] Failed To Load Known Networks From {}: {}", path, error);
+                false
+            },
+        }
+    }
+
+    #[func]
+    fn get_known_networks_path(&self) -> GString {
+        GString::from(self.known_networks.get_path().to_string_lossy().as_ref())
+    }
+
+    fn save_known_networks(&self) -> bool {
+        match self.known_networks.save() {
+            Ok(_) => true,
+            Err(error) => {
+                godot_error!("[SYSTEM] Failed To Save Known Networks: {}", error);
+                false
+            },
+        }
+    }
+
+    /// Each entry has name, position, auto_connect, auto_switch and known keys, in the order Windows tries them.
+    #[func]
+    fn get_preferred_networks(&self) -> Array<Dictionary> {
+        let mut array = Array::new();
+        let Some(guid) = self.active_guid() else { return array };
+        let Some(profiles) = self.network_manager.get_preferred_profiles(&guid) else { return array };
+
+        for profile in &profiles {
+            let mut dictionary = Dictionary::new();
+            dictionary.set("name", profile.name.as_str());
+            dictionary.set("position", profile.position as i64);
+            dictionary.set("auto_connect", profile.policy.auto_connect);
+            dictionary.set("auto_switch", profile.policy.auto_switch);
+            dictionary.set("known", self.known_networks.get_networks().iter().any(|network| network.profile_name == profile.name));
+
+            array.push(&dictionary);
+        }
+
+        array
+    }
+
+    /// Position 0 is the most preferred network.
+    #[func]
+    fn set_preferred_network_position(&mut self, profile_name: GString, position: u32) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        if !self.network_manager.set_profile_position(&guid, &profile_name.to_string(), position) {
+            return false;
+        }
+
+        self.sync_known_network_priorities(&guid)
+    }
+
+    #[func]
+    fn move_preferred_network_up(&mut self, profile_name: GString) -> bool {
+        self.move_preferred_network(profile_name, -1)
+    }
+
+    #[func]
+    fn move_preferred_network_down(&mut self, profile_name: GString) -> bool {
+        self.move_preferred_network(profile_name, 1)
+    }
+
+    #[func]
+    fn set_auto_connect(&mut self, profile_name: GString, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        let name = profile_name.to_string();
+        if !self.network_manager.set_profile_connection_policy(&guid, &name, Some(enabled), None) {
+            return false;
+        }
+
+        match self.known_networks.get_by_profile_mut(&name) {
+            Some(known_network) => {
+                known_network.auto_connect = enabled;
+                self.save_known_networks()
+            },
+            None => true,
+        }
+    }
+
+    #[func]
+    fn set_auto_switch(&mut self, profile_name: GString, enabled: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.set_profile_connection_policy(&guid, &profile_name.to_string(), None, Some(enabled))
+    }
+
+    fn move_preferred_network(&mut self, profile_name: GString, offset: i64) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        let name = profile_name.to_string();
+        let Some(profiles) = self.network_manager.get_preferred_profiles(&guid) else { return false };
+
+        let Some(profile) = profiles.iter().find(|profile| profile.name == name) else {
+            godot_error!("[WLAN] No Profile Named {}", name);
+            return false;
+        };
+
+        let last_position = profiles.len() as i64 - 1;
+        let position = (profile.position as i64 + offset).clamp(0, last_position) as u32;
+        if position == profile.position {
+            return true;
+        }
+
+        if !self.network_manager.set_profile_position(&guid, &name, position) {
+            return false;
+        }
+
+        self.sync_known_network_priorities(&guid)
+    }
+
+    /// Copies the Windows profile order onto the known networks store so both agree on priority.
+    fn sync_known_network_priorities(&mut self, guid: &GUID) -> bool {
+        let Some(profiles) = self.network_manager.get_preferred_profiles(guid) else { return false };
+
+        for profile in &profiles {
+            if let Some(known_network) = self.known_networks.get_by_profile_mut(&profile.name) {
+                known_network.priority = profile.position;
+                known_network.auto_connect = profile.policy.auto_connect;
+            }
+        }
+
+        self.save_known_networks()
+    }
+
+    /// Lets the library pick and join networks: on startup, after a disconnect we didn't ask for,
+    /// and whenever a scan finds a network that beats the current one by the switch margin.
+    #[func]
+    fn set_auto_select_enabled(&mut self, enabled: bool) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.auto_select_enabled = enabled;
+        if !enabled || self.state != WlanApiState::Ready {
+            return true;
+        }
+
+        if let Some(guid) = self.network_manager.get_active_interface_guid() {
+            self.network_manager.request_scan(&guid);
+        }
+        true
+    }
+
+    #[func]
+    fn is_auto_select_enabled(&self) -> bool {
+        self.auto_select_enabled
+    }
+
+    /// Scores the networks from the last scan and connects to the best one.
+    /// Returns the SSID that was chosen, which may be the current network, or an empty string.
+    #[func]
+    fn select_best_network(&mut self) -> GString {
+        let Some(guid) = self.active_guid() else { return GString::new() };
+        self.user_disconnected.remove(&guid);
+        self.cancelled_network = None;
+        self.run_network_selection(&guid);
+
+        GString::from(self.selector.get_last_choice().unwrap_or(""))
+    }
+
+    /// Per-candidate scores from the last selection, best first. Each entry has the candidate's
+    /// inputs plus components, total, eligible, rejected_reason and chosen keys.
+    #[func]
+    fn explain_choice(&self) -> Array<Dictionary> {
+        let last_choice = self.selector.get_last_choice();
+
+        let mut array = Array::new();
+        for score in self.selector.get_last_scores() {
+            let chosen = last_choice == Some(score.candidate.ssid.as_str());
+            array.push(&score.to_dictionary(chosen));
+        }
+
+        array
+    }
+
+    /// Keys are priority, signal, band, security, reliability and captive_portal. Also switches back
+    /// to the weighted policy if a callable policy was set.
+    #[func]
+    fn set_selection_weights(&mut self, weights: Dictionary) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.selection_weights.apply_dictionary(&weights);
+        self.selector.set_policy(Box::new(self.selection_weights.clone()));
+
+        true
+    }
+
+    #[func]
+    fn get_selection_weights(&self) -> Dictionary {
+        self.selection_weights.to_dictionary()
+    }
+
+    /// Replaces the scoring policy with a script function. An invalid callable restores the weighted policy.
+    #[func]
+    fn set_selection_policy(&mut self, policy: Callable) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        if policy.is_valid() {
+            self.selector.set_policy(Box::new(CallablePolicy::new(policy)));
+        } else {
+            self.selector.set_policy(Box::new(self.selection_weights.clone()));
+        }
+
+        true
+    }
+
+    #[func]
+    fn get_selection_policy_name(&self) -> GString {
+        GString::from(self.selector.get_policy_name())
+    }
+
+    /// How many points, out of 100 with the default weights, a network must win by before we switch to it.
+    #[func]
+    fn set_selection_switch_margin(&mut self, margin: f64) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.selector.set_switch_margin(margin);
+
+        true
+    }
+
+    #[func]
+    fn get_selection_switch_margin(&self) -> f64 {
+        self.selector.get_switch_margin()
+    }
+
+    /// Counts against the network in selection. Nothing here probes for captive portals, so this is
+    /// never called automatically: call it when your own portal check hits this network.
+    #[func]
+    fn report_captive_portal(&mut self, ssid: GString) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        if !self.known_networks.record_captive_portal(&ssid.to_string()) {
+            godot_warn!("[SYSTEM] {} Is Not A Known Network", ssid);
+            return false;
+        }
+
+        self.save_known_networks()
+    }
+
+    /// Scans and moves to a stronger access point when the connected one stays weak.
+    /// Stronger BSSes on the same SSID win over other preferred networks.
+    #[func]
+    fn set_roaming_enabled(&mut self, enabled: bool) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.roaming.set_enabled(enabled);
+
+        true
+    }
+
+    #[func]
+    fn is_roaming_enabled(&self) -> bool {
+        self.roaming.is_enabled()
+    }
+
+    /// Signal quality, 0 to 100, below which the current access point counts as weak.
+    #[func]
+    fn set_roaming_threshold(&mut self, quality: u32) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.roaming.config.threshold = quality.min(100);
+
+        true
+    }
+
+    #[func]
+    fn set_roaming_sustain_time(&mut self, seconds: f64) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.roaming.config.sustain = Duration::from_secs_f64(seconds.max(0.0));
+
+        true
+    }
+
+    /// How much stronger, in signal quality, a target has to be than the current access point.
+    #[func]
+    fn set_roaming_hysteresis(&mut self, quality: u32) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.roaming.config.hysteresis = quality.min(100);
 
-    #[signal]
-    fn connection_complete();
+        true
+    }
 
-    #[signal]
-    fn connection_attempt_fail();
+    #[func]
+    fn set_roaming_cooldown(&mut self, seconds: f64) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
 
-    #[signal]
-    fn invalid_password();
+        self.roaming.config.cooldown = Duration::from_secs_f64(seconds.max(0.0));
 
-    #[signal]
-    fn disconnected();
+        true
+    }
 
-    #[cfg(debug_assertions)]
+    /// Reconnects to the same network after a drop the user didn't ask for, backing off between attempts.
     #[func]
-    fn test_xml_data(ssid: GString) {
-        globals::save_xml_to_disk(ssid.to_string().as_str());
+    fn set_auto_reconnect_enabled(&mut self, enabled: bool) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.reconnect.set_enabled(enabled);
+
+        true
     }
 
     #[func]
-    fn initialize_network_manager(&mut self) {
-        self.network_manager.init();
+    fn is_auto_reconnect_enabled(&self) -> bool {
+        self.reconnect.is_enabled()
     }
 
     #[func]
-    fn fetch_network_data(&mut self) {
-        self.network_manager.open_handle();
-        godot_print!("[WLAN] NetworkManager Ready");
-        godot_print!("[WLAN] Scanning for Networks");
+    fn set_reconnect_max_attempts(&mut self, max_attempts: u32) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
 
-        self.network_manager.refresh_networks();
-        self.signals().network_data_fetched().emit();
+        self.reconnect.set_max_attempts(max_attempts);
+
+        true
     }
 
     #[func]
-    fn poll_connection_status(&mut self) {
-        let status_guard = match globals::CONNECTION_NOTIFICATION_CHANNEL.try_lock() {
-            Ok(g) => g,
-            Err(error) => match error {
-                Poisoned(poison_error) => poison_error.into_inner(),
-                WouldBlock => {
-                    godot_warn!("[WLAN] Status Is Currently Locked. Continuing.");
-                    return;
-                },
-            },
-        };
+    fn is_reconnecting(&self) -> bool {
+        self.reconnect.is_active()
+    }
 
-        match status_guard.1.try_recv() {
-            Ok(status_enum) => {
-                match status_enum {
-                    ConnectionNotifcation::ConnectionStart => {
-                        godot_print!("Got Data From Notification Receiver: {:?}", status_enum);
-                        self.signals().connection_start().emit();
-                    }
-                    ConnectionNotifcation::ConnectionComplete => { 
-                        godot_print!("Got Data From Notification Receiver: {:?}", status_enum);
-                        self.signals().connection_complete().emit();
-                        self.interface_state = WlanInterfaceState::Connected 
-                    },
-                    ConnectionNotifcation::Disconnected => {
-                        godot_print!("Got Data From Notification Receiver: {:?}", status_enum);
-                        self.signals().disconnected().emit();
-                        self.interface_state = WlanInterfaceState::Disconnected
-                    },
-                    ConnectionNotifcation::Unknown => self.interface_state = WlanInterfaceState::Unavailable,
-                    _ => {}
-                }
+    #[func]
+    fn cancel_reconnect(&mut self) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
 
-                status_enum
-            },
-            Err(error) => {
-                match error {
-                    Empty => {
-                        if let NotificationState::Empty = self.notif_state {
-                            godot_warn!("[WLAN] Receiver Was Empty");
-                            self.notif_state = NotificationState::StateKnown;
-                            return;
-                        }
+        if self.reconnect.give_up().is_some() {
+            godot_print!("[WLAN] Reconnect Cancelled");
+        }
+        true
+    }
 
-                        return;
-                    },
-                    Disconnected => {
-                        if let NotificationState::Disconnected = self.notif_state {
-                            godot_error!("[WLAN] Notification Receiver Was Disconnected");
-                            self.notif_state = NotificationState::StateKnown;
-                            return;
-                        }
+    fn poll_reconnect(&mut self) {
+        if self.player.is_some() {
+            return;
+        }
+
+        match self.reconnect.poll() {
+            Some(ReconnectStep::Attempt { target, attempt }) => {
+                godot_print!("[WLAN] Reconnecting To {} (Attempt {})", target.ssid, attempt);
+                self.signals().reconnecting().emit(attempt);
 
-                        return;
-                    },
+                self.record_connect(&target.guid, &GString::from(target.ssid.as_str()));
+                if !self.network_manager.connect_to_known_network(&target.guid, &target.profile_name) {
+                    self.reconnect.attempt_failed();
                 }
             },
+            Some(ReconnectStep::GaveUp(target)) => {
+                godot_warn!("[WLAN] Gave Up Reconnecting To {}", target.ssid);
+                self.signals().reconnect_failed().emit();
+
+                // Something else in range may still work
+                if self.auto_select_enabled {
+                    self.auto_select_network(&target.guid);
+                }
+            },
+            None => {},
+        }
+    }
+
+    fn poll_roaming(&mut self) {
+        if self.player.is_some() || !self.roaming.should_scan() {
+            return;
+        }
+
+        let Some(guid) = self.network_manager.get_active_interface_guid() else { return };
+        godot_print!("[WLAN] Signal Weak. Scanning For A Stronger Access Point");
+        self.network_manager.request_scan(&guid);
+    }
+
+    fn try_roam(&mut self, guid: &GUID) {
+        let Some(info) = self.network_manager.get_connection_info(guid) else {
+            self.roaming.reset();
+            return;
         };
+
+        // First preferred network in range that isn't the current one
+        let alternative = self.build_candidates(guid)
+            .into_iter()
+            .filter(|candidate| candidate.ssid != info.ssid && candidate.auto_connect)
+            .min_by_key(|candidate| candidate.priority_rank);
+
+        let Some(interface_data) = self.network_manager.get_interface_data(guid) else { return };
+        let target = self.roaming.choose_target(
+            &info.profile_name,
+            &info.ssid,
+            &info.bssid,
+            &interface_data.bss_entries,
+            alternative.as_ref().map(|candidate| (candidate.profile_name.as_str(), candidate.ssid.as_str())));
+
+        let Some(target) = target else {
+            godot_print!("[WLAN] No Stronger Access Point In Range");
+            return;
+        };
+
+        let (old_bssid, target_bssid) = (convert_bssid_to_string(&info.bssid), convert_bssid_to_string(&target.bssid));
+        godot_print!("[WLAN] Roaming From {} To {} On {} ({})", old_bssid, target_bssid, target.ssid, target.link_quality);
+
+        self.pending_selection = Some(target.profile_name.clone());
+        self.record_connect(guid, &GString::from(target.ssid.as_str()));
+        if !self.network_manager.connect_to_bssid(guid, &target.profile_name, target.bssid) {
+            self.pending_selection = None;
+            return;
+        }
+
+        self.roaming.start_roam(info.bssid, target);
+        self.signals().roam_started().emit(GString::from(old_bssid), GString::from(target_bssid));
     }
 
-    #[func]
-    fn add_network_to_known_networks(&mut self, ssid: GString) {
-        let file_path = Path::new("wlan_data").join("known_networks.txt");
-        if file_path.exists() {
-            godot_warn!("[SYSTEM] File 'known_networks.txt' Already Exists. Returning.");
+    fn observe_signals(&mut self, guid: &GUID) {
+        let readings: HashMap<String, u32> = self.network_manager.get_networks(guid)
+            .values()
+            .map(|network| (network.get_ssid().to_string(), network.get_signal_quality()))
+            .collect();
+
+        self.selector.observe_signals(&readings);
+    }
+
+    fn auto_select_network(&mut self, guid: &GUID) {
+        if self.user_disconnected.contains(guid) || self.pending_selection.is_some() || self.reconnect.is_active() || self.player.is_some() {
             return;
         }
 
-        if self.known_networks.contains(&ssid.to_string()) {
+        let connection_state = self.network_manager.get_interface_data(guid)
+            .map(|data| data.connection_state)
+            .unwrap_or_default();
+
+        // Leave attempts that are already under way alone
+        if !matches!(connection_state, WlanInterfaceState::Connected | WlanInterfaceState::Disconnected) {
             return;
         }
 
-        if let Some(parent) = file_path.parent() {
-            match create_dir_all(parent) {
-                Ok(_) => godot_print!("[SYSTEM] Successfully Created WLAN Data Directory."),
-                Err(error) => {
-                    godot_error!("[SYSTEM] Failed To Create Directory For WLAN Data. Error: {}", error);
+        self.run_network_selection(guid);
+    }
+
+    fn run_network_selection(&mut self, guid: &GUID) {
+        let candidates = self.build_candidates(guid);
+
+        match self.selector.evaluate(&candidates) {
+            Selection::Connect { profile_name, ssid } => {
+                let score = self.selector.get_last_scores()
+                    .iter()
+                    .find(|score| score.candidate.ssid == ssid)
+                    .map_or(0.0, |score| score.total);
+
+                godot_print!("[WLAN] Selected {} With Score {:.1}", ssid, score);
+                self.pending_selection = Some(profile_name.clone());
+                self.record_connect(guid, &GString::from(ssid.as_str()));
+                if !self.network_manager.connect_to_known_network(guid, &profile_name) {
+                    self.pending_selection = None;
                     return;
-                },
+                }
+
+                self.signals().network_selected().emit(GString::from(ssid.as_str()), score);
+            },
+            Selection::Stay => {},
+            Selection::NoCandidates => godot_print!("[WLAN] No Known Networks In Range To Select"),
+        }
+    }
+
+    /// Known networks from the last scan, ranked by the known networks store priority.
+    fn build_candidates(&self, guid: &GUID) -> Vec<Candidate> {
+        let mut known_networks = self.known_networks.get_networks().to_vec();
+        known_networks.sort_by_key(|network| network.priority);
+
+        let Some(interface_data) = self.network_manager.get_interface_data(guid) else { return Vec::new() };
+        let connected_ssid = match interface_data.connection_state {
+            WlanInterfaceState::Connected => self.network_manager.get_connected_network(guid),
+            _ => None,
+        };
+
+        let mut candidates = Vec::new();
+        for (priority_rank, known_network) in known_networks.iter().enumerate() {
+            let ssid = known_network.get_ssid();
+            let Some(network) = interface_data.networks.get(&ssid) else { continue };
+            if self.cancelled_network.as_deref() == Some(ssid.as_str()) {
+                continue;
             }
+
+            let band = interface_data.bss_entries
+                .iter()
+                .filter(|entry| entry.ssid == ssid)
+                .map(|entry| entry.get_band())
+                .max()
+                .unwrap_or(Band::Unknown);
+
+            candidates.push(Candidate {
+                signal_quality: self.selector.get_smoothed_signal(&ssid).unwrap_or(network.get_signal_quality() as f64),
+                profile_name: known_network.profile_name.clone(),
+                priority_rank,
+                known_count: known_networks.len(),
+                band,
+                security: network.get_security(),
+                success_rate: known_network.get_success_rate(),
+                captive_portal_count: known_network.captive_portal_count,
+                auto_connect: known_network.auto_connect,
+                connected: connected_ssid.as_deref() == Some(ssid.as_str()),
+                ssid,
+            });
+        }
+
+        candidates
+    }
+
+    #[func]
+    fn connect(&mut self, ssid: GString) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.user_disconnected.remove(&guid);
+        self.reconnect.cancel();
+        self.begin_connection_attempt(&ssid.to_string());
+        self.record_connect(&guid, &ssid);
+        self.network_manager.connect_to_known_network(&guid, &ssid.to_string())
+    }
+
+    #[func]
+    fn connect_on(&mut self, interface_guid: GString, ssid: GString) -> bool {
+        let Some(guid) = self.target_guid(&interface_guid) else { return false };
+        self.user_disconnected.remove(&guid);
+        self.reconnect.cancel();
+        self.begin_connection_attempt(&ssid.to_string());
+        self.record_connect(&guid, &ssid);
+        self.network_manager.connect_to_known_network(&guid, &ssid.to_string())
+    }
+
+    /// Aborts the connection attempt in progress by disconnecting mid-association. Auto selection
+    /// skips the cancelled network until something connects. `remove_profile` also deletes the
+    /// profile if `generate_profile` created it for this attempt.
+    #[func]
+    fn cancel_connection(&mut self, remove_profile: bool) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+
+        let (connection_state, connecting_ssid) = self.network_manager.get_interface_data(&guid)
+            .map(|data| (data.connection_state, data.connecting_ssid.clone()))
+            .unwrap_or_default();
+        let attempt = self.connection_attempt.take();
+
+        let associating = matches!(connection_state,
+            WlanInterfaceState::Associating | WlanInterfaceState::Authenticating | WlanInterfaceState::Discovering);
+        if !associating && attempt.is_none() && self.pending_selection.is_none() && !self.reconnect.is_active() {
+            godot_warn!("[WLAN] No Connection Attempt To Cancel");
+            return false;
+        }
+
+        // Keep reconnect and roaming from starting it again. Auto selection skips just this network.
+        let reconnect_target = self.reconnect.give_up();
+        self.roaming.reset();
+
+        // Attempts Windows started on its own are only known from their notifications
+        let ssid = attempt.as_ref()
+            .map(|attempt| attempt.ssid.clone())
+            .or(self.pending_selection.take())
+            .or(connecting_ssid)
+            .or(reconnect_target.map(|target| target.ssid));
+        self.cancelled_network = ssid.clone();
+
+        let Some(ssid) = ssid else {
+            godot_print!("[WLAN] Cancelling Connection To An Unknown Network");
+            self.record(TraceEvent::Disconnect { interface: convert_guid_to_string(&guid) });
+            self.network_manager.disconnect_from_network(&guid);
+            return true;
+        };
+
+        godot_print!("[WLAN] Cancelling Connection To {}", ssid);
+        self.record(TraceEvent::Disconnect { interface: convert_guid_to_string(&guid) });
+        self.network_manager.disconnect_from_network(&guid);
+
+        match attempt {
+            Some(attempt) if attempt.transactional => self.roll_back_profile(&guid, attempt),
+            Some(attempt) if remove_profile && attempt.created_profile => {
+                self.delete_profile(attempt.ssid);
+            },
+            _ if remove_profile => godot_print!("[WLAN] Keeping Profile {}. It Was Not Created For This Attempt", ssid),
+            _ => {},
         }
 
-        let mut networks_file = match File::create(file_path) {
-            Ok(file) => file,
+        self.cancelled_attempt = Some(ssid.clone());
+        self.signals().connection_cancelled().emit(GString::from(ssid.as_str()));
+        true
+    }
+
+    fn begin_connection_attempt(&mut self, ssid: &str) {
+        let created_profile = self.created_profile.take().is_some_and(|profile| profile == ssid);
+
+        self.cancelled_attempt = None;
+        self.cancelled_network = None;
+        self.connection_attempt = Some(ConnectionAttempt {
+            ssid: ssid.to_string(),
+            created_profile,
+            transactional: false,
+            previous_profile: None,
+        });
+    }
+
+    /// Saves a profile for `ssid` with `password` and connects, as one step. Any profile with that
+    /// name is backed up first and put back if the connection fails or is cancelled. The network is
+    /// only added to the known networks once it connects. Returns an empty string when the attempt
+    /// started, otherwise what was wrong.
+    #[func]
+    fn connect_with_password(&mut self, ssid: GString, password: GString) -> GString {
+        let Some(guid) = self.active_guid() else { return GString::from("WlanAPI Is Not Ready") };
+        let (ssid_string, password_string) = (ssid.to_string(), password.to_string());
+
+        let Some(network) = self.network_manager.get_network(&guid, &ssid_string) else {
+            godot_error!("[WLAN] Network {} Was Not Found In Scan Results", ssid_string);
+            return GString::from("Network Was Not Found In Scan Results");
+        };
+        let (security, encryption, secured) = (network.get_security(), network.get_encryption(), network.get_secured());
+        let insecure = network.is_insecure();
+        let ssid_bytes = network.get_ssid_bytes().to_vec();
+
+        let requirement = KeyRequirement::for_network(&security, &encryption, secured);
+        let key_format = match validate_key(&password_string, requirement) {
+            Ok(format) => format,
             Err(error) => {
-                godot_error!("[SYSTEM] Failed To Create File 'known_networks.txt'. Error: {}", error);
-                return;
+                godot_warn!("[WLAN] Rejected Password For {}: {}", ssid_string, error.get_message());
+                return GString::from(error.get_message().as_str());
             },
         };
 
-        let ssid_string = ssid.to_string();
-        match networks_file.write_all(format!("{}\n", ssid_string).as_bytes()) {
-            Ok(_) => godot_print!("[SYSTEM] Wrote Data To File 'known_networks.txt'."),
-            Err(error) => godot_error!("[SYSTEM] Failed To Write Data To 'known_networks.txt'. Error: {}", error),
+        let previous_profile = if self.check_for_matching_profile(ssid.clone()) {
+            match self.network_manager.get_profile_xml(&guid, &ssid_string) {
+                Some(profile_xml) => Some(profile_xml),
+                None => return GString::from("Could Not Back Up The Existing Profile"),
+            }
+        } else {
+            None
+        };
+
+        let policy = match self.known_networks.get(&ssid_string) {
+            Some(known_network) => ConnectionPolicy { auto_connect: known_network.auto_connect, ..ConnectionPolicy::default() },
+            None => ConnectionPolicy::default(),
+        };
+
+        let key = get_stored_key(&password_string, key_format, &security, &ssid_bytes, self.store_derived_psk);
+        if insecure {
+            godot_warn!("[WLAN] {} Uses WEP, Which Does Not Protect Traffic From Anyone In Range", ssid_string);
+        }
+
+        let profile_xml = generate_network_profile_xml(&ssid_string, &key, &encryption, &security, &policy, self.wep_key_index);
+        if !self.network_manager.set_wlan_profile(&guid, &profile_xml) {
+            // Nothing was written, so there is nothing to roll back
+            return GString::from("Windows Rejected The Profile");
+        }
+        self.remember_passphrase_strength(&ssid_string, &password_string, key_format);
+
+        self.user_disconnected.remove(&guid);
+        self.reconnect.cancel();
+        self.cancelled_attempt = None;
+        self.cancelled_network = None;
+        self.connection_attempt = Some(ConnectionAttempt {
+            ssid: ssid_string.clone(),
+            created_profile: previous_profile.is_none(),
+            transactional: true,
+            previous_profile,
+        });
+
+        self.record_connect(&guid, &ssid);
+        if !self.network_manager.connect_to_known_network(&guid, &ssid_string) {
+            if let Some(attempt) = self.connection_attempt.take() {
+                self.roll_back_profile(&guid, attempt);
+            }
+            return GString::from("Windows Refused The Connection Request");
+        }
+
+        GString::new()
+    }
+
+    /// Undoes the profile change a `connect_with_password` attempt made.
+    fn roll_back_profile(&mut self, guid: &GUID, attempt: ConnectionAttempt) {
+        self.passphrase_strengths.remove(&attempt.ssid);
+
+        match attempt.previous_profile {
+            Some(profile_xml) => {
+                if self.network_manager.set_wlan_profile(guid, &profile_xml) {
+                    godot_print!("[WLAN] Restored Previous Profile For {}", attempt.ssid);
+                }
+            },
+            None => {
+                godot_print!("[WLAN] Removing Profile For {} After Failed Attempt", attempt.ssid);
+                self.delete_profile(attempt.ssid);
+            },
+        }
+    }
+
+    /// Checks `key` against the security of `ssid` from the last scan without saving anything.
+    /// See `validate_key_for` for the keys of the returned dictionary.
+    #[func]
+    fn validate_network_key(&self, ssid: GString, key: GString) -> Dictionary {
+        let Some(guid) = self.active_guid() else { return Dictionary::new() };
+
+        let Some(network) = self.network_manager.get_network(&guid, &ssid.to_string()) else {
+            let mut dictionary = Dictionary::new();
+            dictionary.set("valid", false);
+            dictionary.set("code", "network_not_found");
+            dictionary.set("message", "Network Was Not Found In Scan Results");
+            return dictionary;
         };
 
-        self.known_networks.push(&GString::from(ssid_string));
+        let requirement = KeyRequirement::for_network(&network.get_security(), &network.get_encryption(), network.get_secured());
+        validation_to_dictionary(&validate_key(&key.to_string(), requirement), requirement)
     }
 
+    /// Checks a key for a network that isn't in the scan results, such as a hidden one.
+    /// `requirement` is "none", "wpa", "wep", "wep40", "wep104" or "any". The dictionary has valid, requirement,
+    /// key_format, code and message keys. code and message are empty when the key is valid.
     #[func]
-    pub fn read_from_known_networks(&mut self) {
-        let mut networks_file = match File::open("wlan_data/known_networks.txt") {
-            Ok(file) => file,
-            Err(error) => {
-                godot_error!("[SYSTEM] Failed To Open File 'known_networks.txt'. Error: {}", error);
-                return;
+    fn validate_key_for(&self, requirement: GString, key: GString) -> Dictionary {
+        let Some(requirement) = KeyRequirement::from_string(&requirement.to_string()) else {
+            godot_error!("[WLAN] Unknown Key Requirement: {}", requirement);
+            return Dictionary::new();
+        };
+
+        validation_to_dictionary(&validate_key(&key.to_string(), requirement), requirement)
+    }
+
+    /// Which of the four WEP key slots, 0 to 3, profiles for WEP networks use. Most access points use 0.
+    #[func]
+    fn set_wep_key_index(&mut self, index: u32) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        if index > 3 {
+            godot_error!("[WLAN] WEP Key Index Must Be 0 To 3, Got {}", index);
+            return false;
+        }
+
+        self.wep_key_index = index;
+        true
+    }
+
+    #[func]
+    fn get_wep_key_index(&self) -> u32 {
+        self.wep_key_index
+    }
+
+    /// On by default. WPA3-SAE and unknown networks always keep the passphrase.
+    #[func]
+    fn set_store_derived_psk(&mut self, enabled: bool) -> bool {
+        if !self.require_configurable() {
+            return false;
+        }
+
+        self.store_derived_psk = enabled;
+        true
+    }
+
+    #[func]
+    fn is_store_derived_psk_enabled(&self) -> bool {
+        self.store_derived_psk
+    }
+
+    /// Returns an empty string once the profile is saved, otherwise what was wrong.
+    #[func]
+    fn generate_profile(&mut self, ssid: GString, password: GString) -> GString {
+        let Some(guid) = self.active_guid() else { return GString::from("WlanAPI Is Not Ready") };
+        let (ssid_string, password_string) = (ssid.to_string(), password.to_string());
+        let network = match self.network_manager.get_network(&guid, ssid_string.as_str()) {
+            Some(network) => network,
+            None => {
+                godot_error!("[WLAN] Network {} Was Not Found In Scan Results", ssid_string);
+                return GString::from("Network Was Not Found In Scan Results");
             },
         };
-        let mut known_networks = String::new();
-        match networks_file.read_to_string(&mut known_networks) {
-            Ok(_) => godot_print!("[SYSTEM] Successfully Read From File 'known_networks.txt'."),
+
+        let requirement = KeyRequirement::for_network(&network.get_security(), &network.get_encryption(), network.get_secured());
+        let key_format = match validate_key(&password_string, requirement) {
+            Ok(format) => format,
             Err(error) => {
-                godot_error!("[SYSTEM] Failed To Read From File 'known_networks.txt'. Error: {}", error);
-                return;
+                godot_warn!("[WLAN] Rejected Password For {}: {}", ssid_string, error.get_message());
+                return GString::from(error.get_message().as_str());
             },
         };
 
-        let s: Vec<GString> = known_networks
-        .lines()
-        .map(|network| network.trim().to_string())
-        .filter(|network| !network.is_empty())
-        .map(|s| GString::from(s))
-        .collect();
+        let policy = match self.known_networks.get(&ssid_string) {
+            Some(known_network) => ConnectionPolicy { auto_connect: known_network.auto_connect, ..ConnectionPolicy::default() },
+            None => ConnectionPolicy::default(),
+        };
+
+        let key = get_stored_key(&password_string, key_format, &network.get_security(), network.get_ssid_bytes(), self.store_derived_psk);
+        if network.is_insecure() {
+            godot_warn!("[WLAN] {} Uses WEP, Which Does Not Protect Traffic From Anyone In Range", ssid_string);
+        }
+
+        let profie = generate_network_profile_xml(ssid_string.as_str(), key.as_str(), &network.get_encryption(), &network.get_security(), &policy, self.wep_key_index);
+        let existed = self.check_for_matching_profile(ssid);
+        if !self.network_manager.set_wlan_profile(&guid, &profie) {
+            return GString::from("Windows Rejected The Profile");
+        }
+        self.remember_passphrase_strength(&ssid_string, &password_string, key_format);
+
+        if !existed {
+            self.created_profile = Some(ssid_string);
+        }
 
-        self.known_networks = Array::from(s.as_slice());
+        GString::new()
     }
 
-    #[func]
-    fn connect(&self, ssid: GString) {
-        let ssid_string = ssid.to_string();
-        self.network_manager.connect_to_known_network(&ssid_string);
+    /// WEP keys aren't rated, the advisor flags WEP itself.
+    fn remember_passphrase_strength(&mut self, ssid: &str, passphrase: &str, key_format: KeyFormat) {
+        match key_format {
+            KeyFormat::Passphrase | KeyFormat::RawPsk => {
+                self.passphrase_strengths.insert(ssid.to_string(), rate_passphrase(passphrase));
+            },
+            _ => {
+                self.passphrase_strengths.remove(ssid);
+            },
+        }
     }
 
+    /// Rates every network from the last scan and every saved profile on the active interface.
+    /// Has networks and profiles keys, each an array of dictionaries with name, kind, rating and
+    /// findings keys. Findings have code, severity, summary and recommendation keys.
     #[func]
-    fn generate_profile(&self, ssid: GString, password: GString) {
-        let (ssid_string, password_string) = (ssid.to_string(), password.to_string());
-        let network = self.network_manager.get_network(ssid.to_string().as_str());
+    fn get_security_report(&self) -> Dictionary {
+        let Some(guid) = self.active_guid() else { return Dictionary::new() };
+
+        let bss_entries = self.network_manager.get_interface_data(&guid).map_or(&[][..], |data| data.bss_entries.as_slice());
+        let mut networks = Array::new();
+        for network in self.network_manager.get_networks(&guid).values() {
+            networks.push(&analyze_network(network, bss_entries).to_dictionary());
+        }
+
+        let mut profiles = Array::new();
+        for profile in self.network_manager.get_profile_list(&guid).unwrap_or_default() {
+            let name = convert_u16_slice_to_string(&profile.strProfileName);
+            let Some(profile_xml) = self.network_manager.get_profile_xml(&guid, &name) else { continue };
+
+            let passphrase_strength = self.passphrase_strengths.get(&name).copied().or_else(|| {
+                self.known_networks.get_networks().iter()
+                    .find(|network| network.profile_name == name)
+                    .and_then(|network| network.passphrase_strength)
+            });
+            profiles.push(&analyze_profile(&name, &profile_xml, passphrase_strength).to_dictionary());
+        }
 
-        let profie = generate_network_profile_xml(ssid_string.as_str(), password_string.as_str(), &network.get_encryption(), &network.get_security());
-        self.network_manager.set_wlan_profile(&profie);
+        let mut dictionary = Dictionary::new();
+        dictionary.set("networks", networks);
+        dictionary.set("profiles", profiles);
+
+        dictionary
     }
 
     #[func]
     fn check_for_matching_profile(&self, ssid: GString) -> bool {
-        let ifo = self.network_manager.get_interface_info().unwrap();
+        let Some(guid) = self.active_guid() else { return false };
 
-        let profiles = match self.network_manager.get_profile_list(ifo) {
+        let profiles = match self.network_manager.get_profile_list(&guid) {
             Some(list) => list,
             None => {
                 godot_error!("[WLAN] Failed To Retrieve Profile List");
@@ -261,70 +2370,130 @@ impl WlanAPI {
     }
 
     #[func]
-    fn check_for_windows_profiles(&mut self) {
-        let ifo = self.network_manager.get_interface_info().unwrap();
+    fn check_for_windows_profiles(&mut self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
 
-        if let Some(profile_list) = self.network_manager.get_profile_list(ifo) {
-            if let Some((ssid, _)) = self.network_manager.check_for_windows_profiles(&profile_list) {
+        if let Some(profile_list) = self.network_manager.get_profile_list(&guid) {
+            if let Some((ssid, _)) = self.network_manager.check_for_windows_profiles(&guid, &profile_list) {
                 let ssid_gstring = GString::from(ssid);
                 self.signals().windows_profiles_found().emit(ssid_gstring);
             }
         }
+
+        true
     }
 
     #[func]
-    fn delete_profile(&self, ssid: String) {
+    fn delete_profile(&self, ssid: String) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
         let handle = self.network_manager.get_client_handle();
-        let ifo = self.network_manager.get_interface_info().unwrap();
 
         let wide_ssid = match convert_string_to_u16cstring(&ssid) {
             Some(wide) => wide,
-            None => return
+            None => return false
         };
 
-        wlan::delete_profile(handle, &ifo.InterfaceGuid, &wide_ssid);
+        wlan::delete_profile(handle, &guid, &wide_ssid)
+    }
+
+    #[func]
+    fn disconnect(&mut self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.user_disconnected.insert(guid);
+        self.reconnect.cancel();
+        self.record(TraceEvent::Disconnect { interface: convert_guid_to_string(&guid) });
+        self.network_manager.disconnect_from_network(&guid);
+        true
+    }
+
+    #[func]
+    fn disconnect_on(&mut self, interface_guid: GString) -> bool {
+        let Some(guid) = self.target_guid(&interface_guid) else { return false };
+        self.user_disconnected.insert(guid);
+        self.reconnect.cancel();
+        self.record(TraceEvent::Disconnect { interface: convert_guid_to_string(&guid) });
+        self.network_manager.disconnect_from_network(&guid);
+        true
     }
 
     #[func]
-    fn disconnect(&self) {
-        self.network_manager.disconnect_from_network();
+    fn scan_networks(&mut self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        self.network_manager.request_scan(&guid);
+        true
     }
 
     #[func]
-    fn scan_networks(&mut self) {
-        self.network_manager.request_scan();
+    fn scan_networks_on(&mut self, interface_guid: GString) -> bool {
+        let Some(guid) = self.target_guid(&interface_guid) else { return false };
+        self.network_manager.request_scan(&guid);
+        true
     }
 
     #[func]
-    fn refresh_network_data(&mut self) {
+    fn refresh_network_data(&mut self) -> bool {
         godot_print!("[WLAN] Refreshing NetworkData");
 
-        self.network_manager.refresh_networks();
+        let Some(guid) = self.active_guid() else { return false };
+        self.refresh_networks_for(&guid);
+        self.signals().network_data_fetched().emit();
+        true
+    }
+
+    #[func]
+    fn refresh_network_data_on(&mut self, interface_guid: GString) -> bool {
+        let Some(guid) = self.target_guid(&interface_guid) else { return false };
+        self.refresh_networks_for(&guid);
         self.signals().network_data_fetched().emit();
+        true
     }
 
 
     #[func]
     fn check_for_active_connection(&mut self) -> bool {
-        match self.network_manager.check_for_active_connection() {
+        let Some(guid) = self.active_guid() else { return false };
+
+        let connected = match self.network_manager.check_for_active_connection(&guid) {
             Some(_) => {
                 godot_print!("[WLAN] Interface Is Connected");
-                self.interface_state = WlanInterfaceState::Connected;
                 true
             },
             None => {
                 godot_print!("[WLAN] Interface Is Disconnected");
-                self.interface_state = WlanInterfaceState::Disconnected;
                 false
             },
-        }
+        };
+
+        self.network_manager.get_interface_data_mut(&guid).connection_state = if connected {
+            WlanInterfaceState::Connected
+        } else {
+            WlanInterfaceState::Disconnected
+        };
+
+        connected
     }
 
     #[func]
     fn get_connected_ssid(&self) -> Variant {
-        let result = match self.interface_state {
+        let Some(guid) = self.active_guid() else { return Variant::nil() };
+        self.connected_ssid_for(&guid)
+    }
+
+    #[func]
+    fn get_connected_ssid_on(&self, interface_guid: GString) -> Variant {
+        let Some(guid) = self.target_guid(&interface_guid) else { return Variant::nil() };
+        self.connected_ssid_for(&guid)
+    }
+
+    fn connected_ssid_for(&self, guid: &GUID) -> Variant {
+        let interface_state = match self.network_manager.get_interface_data(guid) {
+            Some(data) => data.connection_state,
+            None => WlanInterfaceState::default(),
+        };
+
+        let result = match interface_state {
             WlanInterfaceState::Connected => {
-                let ssid = match self.network_manager.get_connected_network() {
+                let ssid = match self.network_manager.get_connected_network(guid) {
                     Some(ssid) => Variant::from(ssid),
                     None => Variant::nil(),
                 };
@@ -337,7 +2506,7 @@ impl WlanAPI {
             }
 
             _ => {
-                godot_error!("ERROR: REACHED WILD CARD OF INTERFACE STATE MATCH IN GET CONNECTED SSID {:?}", self.interface_state);
+                godot_error!("ERROR: REACHED WILD CARD OF INTERFACE STATE MATCH IN GET CONNECTED SSID {:?}", interface_state);
                 Variant::nil()
             }
         };
@@ -345,6 +2514,95 @@ impl WlanAPI {
         result
     }
 
+    /// Starts probing the active interface's link. The probes can take several seconds, so the
+    /// report arrives through `diagnostics_completed` while notifications are being processed.
+    #[func]
+    fn run_link_diagnostics(&mut self) -> bool {
+        let Some(guid) = self.active_guid() else { return false };
+        if self.pending_diagnostics.is_some() {
+            godot_warn!("[WLAN] Link Diagnostics Are Already Running");
+            return false;
+        }
+
+        self.pending_diagnostics = self.network_manager.start_link_diagnostics(&guid);
+        self.pending_diagnostics.is_some()
+    }
+
+    #[func]
+    fn is_running_link_diagnostics(&self) -> bool {
+        self.pending_diagnostics.is_some()
+    }
+
+    fn poll_diagnostics(&mut self) {
+        let Some(receiver) = self.pending_diagnostics.as_ref() else { return };
+
+        let diagnostics = match receiver.try_recv() {
+            Ok(diagnostics) => diagnostics,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                godot_error!("[WLAN] Link Diagnostics Ended Without A Report");
+                self.pending_diagnostics = None;
+                return;
+            },
+        };
+
+        self.pending_diagnostics = None;
+        let report = Self::diagnostics_report(&diagnostics);
+        self.signals().diagnostics_completed().emit(&report);
+    }
+
+    fn diagnostics_report(diagnostics: &LinkDiagnostics) -> Gd<WiFiDiagnostics> {
+        let to_strings = |addresses: &Vec<std::net::IpAddr>| {
+            addresses.iter().map(|address| GString::from(address.to_string())).collect::<PackedStringArray>()
+        };
+        let latency = |probe: &ProbeResult| probe.latency_ms.map(|ms| ms as i64).unwrap_or(-1);
+
+        let mut report = WiFiDiagnostics::new_gd();
+        let mut report_bind = report.bind_mut();
+
+        report_bind.ipv4_addresses = to_strings(&diagnostics.ipv4_addresses);
+        report_bind.ipv6_addresses = to_strings(&diagnostics.ipv6_addresses);
+        report_bind.gateways = to_strings(&diagnostics.gateways);
+        report_bind.dns_servers = to_strings(&diagnostics.dns_servers);
+        report_bind.gateway_reachable = diagnostics.gateway_probe.success;
+        report_bind.gateway_latency_ms = latency(&diagnostics.gateway_probe);
+        report_bind.dns_resolved = diagnostics.dns_probe.success;
+        report_bind.dns_latency_ms = latency(&diagnostics.dns_probe);
+        report_bind.tcp_connected = diagnostics.tcp_probe.success;
+        report_bind.tcp_latency_ms = latency(&diagnostics.tcp_probe);
+
+        drop(report_bind);
+        report
+    }
+
+    #[func]
+    fn get_connection_info(&self) -> Option<Gd<WiFiConnection>> {
+        let guid = self.active_guid()?;
+        let info = self.network_manager.get_connection_info(&guid)?;
+
+        let mut connection = WiFiConnection::new_gd();
+        let mut connection_bind = connection.bind_mut();
+
+        connection_bind.profile_name = GString::from(info.profile_name.as_str());
+        connection_bind.ssid = GString::from(info.ssid.as_str());
+        connection_bind.bssid = GString::from(convert_bssid_to_string(&info.bssid));
+        connection_bind.phy_type = GString::from(info.phy_type.as_str());
+        connection_bind.signal_quality = info.signal_quality;
+        connection_bind.rssi = info.rssi.unwrap_or(0);
+        connection_bind.rx_rate_kbps = info.rx_rate_kbps;
+        connection_bind.tx_rate_kbps = info.tx_rate_kbps;
+        connection_bind.channel = info.channel.unwrap_or(0);
+        connection_bind.frequency_mhz = info.frequency_mhz.unwrap_or(0);
+        connection_bind.security_enabled = info.security_enabled;
+        connection_bind.auth_algorithm = GString::from(info.auth_algorithm.as_str());
+        connection_bind.cipher_algorithm = GString::from(info.cipher_algorithm.as_str());
+        connection_bind.one_x_enabled = info.one_x_enabled;
+        connection_bind.connection_mode = GString::from(info.connection_mode.as_str());
+
+        drop(connection_bind);
+        Some(connection)
+    }
+
     #[allow(unused)]
     fn check_network_connectivity(&self, _ssid: GString) {
         todo!();
@@ -361,7 +2619,19 @@ impl WlanAPI {
 
     #[func]
     fn get_networks(&self) -> Dictionary {
-        let networks = self.network_manager.get_networks();
+        let Some(guid) = self.active_guid() else { return Dictionary::new() };
+        self.networks_for(&guid)
+    }
+
+    #[func]
+    fn get_networks_on(&self, interface_guid: GString) -> Dictionary {
+        let Some(guid) = self.target_guid(&interface_guid) else { return Dictionary::new() };
+        self.networks_for(&guid)
+    }
+
+    fn networks_for(&self, guid: &GUID) -> Dictionary {
+        let networks = self.network_manager.get_networks(guid);
+        let bss_entries = self.network_manager.get_interface_data(guid).map_or(&[][..], |data| data.bss_entries.as_slice());
         let mut networks_dictionary = Dictionary::new();
 
         for (ssid, network) in networks {
@@ -371,6 +2641,12 @@ impl WlanAPI {
             wifi_bind.ssid = GString::from(ssid.as_ref());
             wifi_bind.network_security = network.get_security();
             wifi_bind.secured = network.get_secured();
+            wifi_bind.encryption = network.get_encryption();
+            wifi_bind.insecure = network.is_insecure();
+
+            let report = analyze_network(&network, bss_entries);
+            wifi_bind.security_rating = GString::from(report.get_rating().as_str());
+            wifi_bind.security_findings = report.findings_to_array();
             wifi_bind.bars = network.get_bars();
 
             let wifi_ssid = wifi_bind.ssid.clone();
@@ -383,7 +2659,12 @@ impl WlanAPI {
     }
 
     #[func]
-    fn close_wlan_handle(&self) {
+    fn close_wlan_handle(&mut self) {
+        self.set_state(WlanApiState::ShuttingDown);
+        self.player = None;
+        self.recorder = None;
+        self.pending_diagnostics = None;
+
         match self.network_manager.close_handle() {
             Ok(_) => godot_print!("[WLAN] Closing WlanHandle"),
             Err(e) => godot_print!("[WLAN] Failed to Close WlanHandle: {:?}", e)